@@ -0,0 +1,6 @@
+CREATE TABLE report_nonces (
+    device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    nonce VARCHAR NOT NULL,
+    timestamp TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (device_id, nonce)
+);
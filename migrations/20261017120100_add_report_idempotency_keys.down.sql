@@ -0,0 +1,2 @@
+DROP INDEX reports_device_id_idempotency_key_idx;
+ALTER TABLE reports DROP COLUMN idempotency_key;
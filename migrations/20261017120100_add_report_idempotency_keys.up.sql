@@ -0,0 +1,2 @@
+ALTER TABLE reports ADD COLUMN idempotency_key VARCHAR;
+CREATE UNIQUE INDEX reports_device_id_idempotency_key_idx ON reports (device_id, idempotency_key);
@@ -0,0 +1 @@
+ALTER TABLE devices ADD COLUMN osmand_enabled BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,5 @@
+CREATE TABLE device_friends (
+    device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    friend_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    PRIMARY KEY (device_id, friend_id)
+);
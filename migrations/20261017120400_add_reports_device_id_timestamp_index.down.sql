@@ -0,0 +1 @@
+DROP INDEX reports_device_id_timestamp_id_idx;
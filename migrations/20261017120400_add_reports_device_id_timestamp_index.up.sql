@@ -0,0 +1 @@
+CREATE INDEX reports_device_id_timestamp_id_idx ON reports (device_id, timestamp, id);
@@ -0,0 +1,18 @@
+CREATE TABLE import_jobs (
+    id UUID PRIMARY KEY,
+    device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    path VARCHAR NOT NULL,
+    uploaded BOOLEAN NOT NULL DEFAULT FALSE,
+    status VARCHAR NOT NULL DEFAULT 'pending',
+    total_bytes BIGINT NOT NULL DEFAULT 0,
+    processed_bytes BIGINT NOT NULL DEFAULT 0,
+    processed_records BIGINT NOT NULL DEFAULT 0,
+    imported BIGINT NOT NULL DEFAULT 0,
+    duplicates BIGINT NOT NULL DEFAULT 0,
+    rejected BIGINT NOT NULL DEFAULT 0,
+    error VARCHAR,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX import_jobs_status_idx ON import_jobs (status);
@@ -0,0 +1,3 @@
+DROP INDEX reports_device_id_sequence_idx;
+ALTER TABLE reports DROP COLUMN sequence;
+ALTER TABLE devices DROP COLUMN last_report_sequence;
@@ -0,0 +1,19 @@
+ALTER TABLE devices ADD COLUMN last_report_sequence BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE reports ADD COLUMN sequence BIGINT;
+
+UPDATE reports SET sequence = numbered.sequence
+FROM (
+    SELECT id, row_number() OVER (
+        PARTITION BY device_id ORDER BY submit_timestamp ASC NULLS FIRST, timestamp ASC, id ASC
+    ) AS sequence
+    FROM reports
+) numbered
+WHERE reports.id = numbered.id;
+
+UPDATE devices SET last_report_sequence = COALESCE(
+    (SELECT max(sequence) FROM reports WHERE reports.device_id = devices.id),
+    0
+);
+
+ALTER TABLE reports ALTER COLUMN sequence SET NOT NULL;
+CREATE UNIQUE INDEX reports_device_id_sequence_idx ON reports (device_id, sequence);
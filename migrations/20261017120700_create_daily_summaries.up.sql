@@ -0,0 +1,21 @@
+CREATE TABLE daily_summaries (
+    device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    timezone VARCHAR NOT NULL,
+    day DATE NOT NULL,
+    start_timestamp TIMESTAMP WITH TIME ZONE NOT NULL,
+    end_timestamp TIMESTAMP WITH TIME ZONE NOT NULL,
+    reports BIGINT NOT NULL,
+    distance DOUBLE PRECISION NOT NULL,
+    moving_time DOUBLE PRECISION NOT NULL,
+    trips BIGINT NOT NULL,
+    first_report TIMESTAMP WITH TIME ZONE,
+    last_report TIMESTAMP WITH TIME ZONE,
+    min_latitude DOUBLE PRECISION,
+    min_longitude DOUBLE PRECISION,
+    max_latitude DOUBLE PRECISION,
+    max_longitude DOUBLE PRECISION,
+    PRIMARY KEY (device_id, timezone, day)
+);
+
+CREATE INDEX daily_summaries_device_id_start_timestamp_idx
+    ON daily_summaries (device_id, start_timestamp);
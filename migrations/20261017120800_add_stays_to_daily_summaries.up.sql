@@ -0,0 +1,2 @@
+ALTER TABLE daily_summaries ADD COLUMN stays BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE daily_summaries ALTER COLUMN stays DROP DEFAULT;
@@ -0,0 +1,10 @@
+CREATE TABLE places (
+    id UUID PRIMARY KEY,
+    device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    radius DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (device_id, name)
+);
@@ -0,0 +1,2 @@
+DROP TABLE geofence_events;
+DROP TABLE geofences;
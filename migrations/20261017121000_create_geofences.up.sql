@@ -0,0 +1,27 @@
+CREATE TABLE geofences (
+    id UUID PRIMARY KEY,
+    device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    latitude DOUBLE PRECISION,
+    longitude DOUBLE PRECISION,
+    radius DOUBLE PRECISION,
+    geometry VARCHAR,
+    hysteresis DOUBLE PRECISION NOT NULL,
+    inside BOOLEAN NOT NULL DEFAULT FALSE,
+    state_timestamp TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (device_id, name),
+    CHECK ((radius IS NULL) = (latitude IS NULL) AND (radius IS NULL) = (longitude IS NULL)),
+    CHECK ((radius IS NULL) <> (geometry IS NULL))
+);
+
+CREATE TABLE geofence_events (
+    id UUID PRIMARY KEY,
+    geofence_id UUID NOT NULL REFERENCES geofences (id) ON DELETE CASCADE,
+    device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    report_id UUID NOT NULL REFERENCES reports (id) ON DELETE CASCADE,
+    kind VARCHAR NOT NULL,
+    timestamp TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX geofence_events_device_id_timestamp_idx ON geofence_events (device_id, timestamp);
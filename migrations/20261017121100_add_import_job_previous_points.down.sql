@@ -0,0 +1,4 @@
+ALTER TABLE import_jobs
+    DROP COLUMN previous_timestamp,
+    DROP COLUMN previous_latitude,
+    DROP COLUMN previous_longitude;
@@ -0,0 +1,4 @@
+ALTER TABLE import_jobs
+    ADD COLUMN previous_timestamp TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN previous_latitude DOUBLE PRECISION,
+    ADD COLUMN previous_longitude DOUBLE PRECISION;
@@ -0,0 +1 @@
+DROP FUNCTION area_contains;
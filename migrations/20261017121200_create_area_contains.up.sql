@@ -0,0 +1,22 @@
+-- Returns whether a position lies within an area, given its exterior rings and holes as polygon
+-- literals and, for each hole, the one-based index of the exterior ring containing it. A NULL area
+-- contains every position, so that queries may filter by an optional area.
+CREATE FUNCTION area_contains(
+    exteriors TEXT[],
+    holes TEXT[],
+    hole_polygons INTEGER[],
+    latitude NUMERIC,
+    longitude NUMERIC
+) RETURNS BOOLEAN
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT exteriors IS NULL OR EXISTS (
+        SELECT 1 FROM unnest(exteriors) WITH ORDINALITY AS exterior (ring, index)
+        WHERE exterior.ring::polygon @> point(longitude::float8, latitude::float8)
+            AND NOT EXISTS (
+                SELECT 1 FROM unnest(holes, hole_polygons) AS hole (ring, polygon)
+                WHERE hole.polygon = exterior.index
+                    AND hole.ring::polygon @> point(longitude::float8, latitude::float8)
+            )
+    )
+$$;
@@ -0,0 +1,19 @@
+CREATE OR REPLACE FUNCTION area_contains(
+    exteriors TEXT[],
+    holes TEXT[],
+    hole_polygons INTEGER[],
+    latitude NUMERIC,
+    longitude NUMERIC
+) RETURNS BOOLEAN
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT exteriors IS NULL OR EXISTS (
+        SELECT 1 FROM unnest(exteriors) WITH ORDINALITY AS exterior (ring, index)
+        WHERE exterior.ring::polygon @> point(longitude::float8, latitude::float8)
+            AND NOT EXISTS (
+                SELECT 1 FROM unnest(holes, hole_polygons) AS hole (ring, polygon)
+                WHERE hole.polygon = exterior.index
+                    AND hole.ring::polygon @> point(longitude::float8, latitude::float8)
+            )
+    )
+$$;
@@ -0,0 +1,24 @@
+-- Polygons crossing the antimeridian have longitudes beyond 180 degrees, so they may contain a
+-- position one turn to the east or west of its longitude.
+CREATE OR REPLACE FUNCTION area_contains(
+    exteriors TEXT[],
+    holes TEXT[],
+    hole_polygons INTEGER[],
+    latitude NUMERIC,
+    longitude NUMERIC
+) RETURNS BOOLEAN
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT exteriors IS NULL OR EXISTS (
+        SELECT 1
+        FROM unnest(exteriors) WITH ORDINALITY AS exterior (ring, index),
+            unnest(ARRAY[-360.0, 0.0, 360.0]::float8[]) AS shift (degrees)
+        WHERE exterior.ring::polygon @> point(longitude::float8 + shift.degrees, latitude::float8)
+            AND NOT EXISTS (
+                SELECT 1 FROM unnest(holes, hole_polygons) AS hole (ring, polygon)
+                WHERE hole.polygon = exterior.index
+                    AND hole.ring::polygon
+                        @> point(longitude::float8 + shift.degrees, latitude::float8)
+            )
+    )
+$$;
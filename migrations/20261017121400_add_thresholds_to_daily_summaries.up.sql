@@ -0,0 +1,3 @@
+-- Existing summaries have no recorded thresholds, so they are recomputed when next requested.
+ALTER TABLE daily_summaries ADD COLUMN thresholds VARCHAR NOT NULL DEFAULT '';
+ALTER TABLE daily_summaries ALTER COLUMN thresholds DROP DEFAULT;
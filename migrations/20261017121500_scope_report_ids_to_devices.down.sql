@@ -0,0 +1,7 @@
+ALTER TABLE geofence_events DROP CONSTRAINT geofence_events_device_id_report_id_fkey;
+ALTER TABLE reports DROP CONSTRAINT reports_pkey;
+ALTER TABLE reports ADD PRIMARY KEY (id);
+
+ALTER TABLE geofence_events
+    ADD CONSTRAINT geofence_events_report_id_fkey FOREIGN KEY (report_id)
+    REFERENCES reports (id) ON DELETE CASCADE;
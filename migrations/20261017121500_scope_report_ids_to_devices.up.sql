@@ -0,0 +1,8 @@
+-- Report IDs are chosen by clients, so they are only unique for each device.
+ALTER TABLE geofence_events DROP CONSTRAINT geofence_events_report_id_fkey;
+ALTER TABLE reports DROP CONSTRAINT reports_pkey;
+ALTER TABLE reports ADD PRIMARY KEY (device_id, id);
+
+ALTER TABLE geofence_events
+    ADD CONSTRAINT geofence_events_device_id_report_id_fkey FOREIGN KEY (device_id, report_id)
+    REFERENCES reports (device_id, id) ON DELETE CASCADE;
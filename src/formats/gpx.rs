@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use roxmltree::{Document, Node};
+use time::{Duration, OffsetDateTime, UtcOffset, format_description::well_known::Rfc3339};
+
+use crate::formats::{Track, TrackPoint, escape_xml, parse_timestamp};
+use crate::models::Report;
+
+/// Incrementally writes reports as a GPX 1.1 document containing a single track.
+///
+/// Consecutive reports separated by more than the configured gap are placed in separate track
+/// segments. Speed and course are written using the Garmin `TrackPointExtension` schema, as GPX 1.1
+/// has no elements of its own for them.
+pub struct GpxWriter {
+    gap: Duration,
+    previous: Option<OffsetDateTime>,
+}
+
+impl GpxWriter {
+    #[must_use]
+    pub fn new(gap: Duration) -> Self {
+        Self {
+            gap,
+            previous: None,
+        }
+    }
+
+    #[must_use]
+    pub fn header(name: &str) -> String {
+        format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                "\n",
+                r#"<gpx version="1.1" creator="com_calindora_follow" "#,
+                r#"xmlns="http://www.topografix.com/GPX/1/1" "#,
+                r#"xmlns:gpxtpx="http://www.garmin.com/xmlschemas/TrackPointExtension/v2" "#,
+                r#"xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" "#,
+                r#"xsi:schemaLocation="http://www.topografix.com/GPX/1/1 http://www.topografix.com/GPX/1/1/gpx.xsd">"#,
+                "\n<trk>\n<name>{}</name>\n"
+            ),
+            escape_xml(name)
+        )
+    }
+
+    pub fn write(&mut self, output: &mut String, report: &Report) {
+        match self.previous {
+            None => output.push_str("<trkseg>\n"),
+            Some(previous) if report.timestamp - previous > self.gap => {
+                output.push_str("</trkseg>\n<trkseg>\n");
+            }
+            Some(_) => {}
+        }
+
+        self.previous = Some(report.timestamp);
+
+        // Course is restricted to [0, 360) by the extension schema.
+        let course = if report.bearing >= 360 {
+            &report.bearing - BigDecimal::from(360)
+        } else {
+            report.bearing.clone()
+        };
+
+        let time = report
+            .timestamp
+            .to_offset(UtcOffset::UTC)
+            .format(&Rfc3339)
+            .unwrap_or_default();
+
+        output.push_str(&format!(
+            concat!(
+                "<trkpt lat=\"{}\" lon=\"{}\">",
+                "<ele>{}</ele><time>{}</time>",
+                "<extensions><gpxtpx:TrackPointExtension>",
+                "<gpxtpx:speed>{}</gpxtpx:speed><gpxtpx:course>{}</gpxtpx:course>",
+                "</gpxtpx:TrackPointExtension></extensions>",
+                "</trkpt>\n"
+            ),
+            report.latitude, report.longitude, report.altitude, time, report.speed, course,
+        ));
+    }
+
+    #[must_use]
+    pub fn footer(&self) -> String {
+        if self.previous.is_some() {
+            "</trkseg>\n</trk>\n</gpx>\n".to_string()
+        } else {
+            "</trk>\n</gpx>\n".to_string()
+        }
+    }
+}
+
+/// Reads the track points of every track in a GPX document.
+///
+/// Speed and course are read from either the Garmin `TrackPointExtension` schema or the elements
+/// of the same name defined by GPX 1.0.
+#[must_use]
+pub fn parse(document: &Document) -> Track {
+    let mut track = Track::default();
+
+    for node in document
+        .descendants()
+        .filter(|node| node.has_tag_name("trkpt"))
+    {
+        match parse_point(node) {
+            Some(point) => track.points.push(point),
+            None => track.invalid += 1,
+        }
+    }
+
+    track
+}
+
+fn parse_point(node: Node) -> Option<TrackPoint> {
+    let child = |name: &str| {
+        node.descendants()
+            .find(|child| child.has_tag_name(name))
+            .and_then(|child| child.text())
+            .and_then(|text| BigDecimal::from_str(text.trim()).ok())
+    };
+
+    Some(TrackPoint {
+        timestamp: node
+            .children()
+            .find(|child| child.has_tag_name("time"))
+            .and_then(|child| child.text())
+            .and_then(parse_timestamp)?,
+        latitude: BigDecimal::from_str(node.attribute("lat")?.trim()).ok()?,
+        longitude: BigDecimal::from_str(node.attribute("lon")?.trim()).ok()?,
+        altitude: child("ele"),
+        speed: child("speed"),
+        bearing: child("course"),
+        accuracy: None,
+    })
+}
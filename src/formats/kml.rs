@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use roxmltree::{Document, Node};
+
+use crate::formats::{Track, TrackPoint, parse_timestamp};
+
+/// Reads the points of a KML document.
+///
+/// Points are read from `gx:Track` elements, which pair each `when` element with the `gx:coord`
+/// element in the same position, and from placemarks containing both a `TimeStamp` and a `Point`.
+/// Placemarks without a timestamp cannot be converted to reports and are ignored.
+#[must_use]
+pub fn parse(document: &Document) -> Track {
+    let mut track = Track::default();
+
+    for node in document.descendants() {
+        if node.has_tag_name("Track") {
+            parse_track(node, &mut track);
+        } else if node.has_tag_name("Placemark") {
+            parse_placemark(node, &mut track);
+        }
+    }
+
+    track
+}
+
+fn parse_track(node: Node, track: &mut Track) {
+    let whens = node
+        .children()
+        .filter(|child| child.has_tag_name("when"))
+        .map(|child| child.text().and_then(parse_timestamp));
+
+    let coords = node
+        .children()
+        .filter(|child| child.has_tag_name("coord"))
+        .map(|child| child.text().and_then(|text| parse_coordinates(text, ' ')));
+
+    for (timestamp, coordinates) in whens.zip(coords) {
+        match (timestamp, coordinates) {
+            (Some(timestamp), Some((longitude, latitude, altitude))) => {
+                track.points.push(TrackPoint {
+                    timestamp,
+                    latitude,
+                    longitude,
+                    altitude,
+                    speed: None,
+                    bearing: None,
+                    accuracy: None,
+                });
+            }
+            _ => track.invalid += 1,
+        }
+    }
+}
+
+fn parse_placemark(node: Node, track: &mut Track) {
+    let Some(when) = node
+        .children()
+        .find(|child| child.has_tag_name("TimeStamp"))
+        .and_then(|child| child.children().find(|child| child.has_tag_name("when")))
+    else {
+        return;
+    };
+
+    let Some(point) = node.children().find(|child| child.has_tag_name("Point")) else {
+        return;
+    };
+
+    let timestamp = when.text().and_then(parse_timestamp);
+    let coordinates = point
+        .children()
+        .find(|child| child.has_tag_name("coordinates"))
+        .and_then(|child| child.text())
+        .and_then(|text| parse_coordinates(text, ','));
+
+    match (timestamp, coordinates) {
+        (Some(timestamp), Some((longitude, latitude, altitude))) => {
+            track.points.push(TrackPoint {
+                timestamp,
+                latitude,
+                longitude,
+                altitude,
+                speed: None,
+                bearing: None,
+                accuracy: None,
+            });
+        }
+        _ => track.invalid += 1,
+    }
+}
+
+/// Parses a longitude, latitude and optional altitude separated by the given separator.
+fn parse_coordinates(
+    text: &str,
+    separator: char,
+) -> Option<(BigDecimal, BigDecimal, Option<BigDecimal>)> {
+    let mut values = text
+        .trim()
+        .split(separator)
+        .filter(|value| !value.is_empty())
+        .map(|value| BigDecimal::from_str(value.trim()));
+
+    let longitude = values.next()?.ok()?;
+    let latitude = values.next()?.ok()?;
+    let altitude = values.next().transpose().ok()?;
+
+    Some((longitude, latitude, altitude))
+}
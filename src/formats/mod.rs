@@ -0,0 +1,69 @@
+use bigdecimal::BigDecimal;
+use time::{
+    OffsetDateTime,
+    format_description::well_known::{Iso8601, Rfc3339},
+};
+
+pub mod gpx;
+pub mod kml;
+pub mod takeout;
+
+/// A single timestamped point read from a track file.
+#[derive(Debug)]
+pub struct TrackPoint {
+    pub timestamp: OffsetDateTime,
+    pub latitude: BigDecimal,
+    pub longitude: BigDecimal,
+    pub altitude: Option<BigDecimal>,
+    pub speed: Option<BigDecimal>,
+    pub bearing: Option<BigDecimal>,
+    pub accuracy: Option<BigDecimal>,
+}
+
+/// The points read from a track file, along with the number of points that were skipped because
+/// they lacked a usable timestamp or position.
+#[derive(Debug, Default)]
+pub struct Track {
+    pub points: Vec<TrackPoint>,
+    pub invalid: u64,
+}
+
+/// Parses a GPX or KML document, detecting the format from its root element.
+pub fn parse_track(document: &str) -> anyhow::Result<Track> {
+    let document = roxmltree::Document::parse(document)?;
+    let root = document.root_element();
+
+    match root.tag_name().name() {
+        "gpx" => Ok(gpx::parse(&document)),
+        "kml" => Ok(kml::parse(&document)),
+        other => anyhow::bail!("Unsupported document type: {other}"),
+    }
+}
+
+/// Parses an XML Schema `dateTime`, as used by both GPX and KML.
+fn parse_timestamp(value: &str) -> Option<OffsetDateTime> {
+    let value = value.trim();
+
+    OffsetDateTime::parse(value, &Rfc3339)
+        .or_else(|_| OffsetDateTime::parse(value, &Iso8601::DEFAULT))
+        .ok()
+}
+
+/// Escapes the characters that may not appear literally in XML text or attribute values.
+#[must_use]
+pub fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,320 @@
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, num_bigint::BigInt};
+use serde::{
+    Deserialize, Deserializer,
+    de::{DeserializeSeed, Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
+};
+use time::OffsetDateTime;
+
+use crate::formats::{TrackPoint, parse_timestamp};
+
+/// A record from the `locations` array of the original `Records.json` export.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    latitude_e7: Option<i64>,
+    longitude_e7: Option<i64>,
+    accuracy: Option<i64>,
+    altitude: Option<i64>,
+    velocity: Option<i64>,
+    heading: Option<i64>,
+    timestamp: Option<String>,
+    timestamp_ms: Option<String>,
+}
+
+/// A segment from the `semanticSegments` array of the newer on-device timeline export.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Segment {
+    start_time: Option<String>,
+    end_time: Option<String>,
+    #[serde(default)]
+    timeline_path: Vec<PathPoint>,
+    visit: Option<Visit>,
+    activity: Option<Activity>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PathPoint {
+    point: Option<String>,
+    time: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Visit {
+    top_candidate: Option<VisitCandidate>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct VisitCandidate {
+    place_location: Option<LatLng>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Activity {
+    start: Option<LatLng>,
+    end: Option<LatLng>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LatLng {
+    lat_lng: Option<String>,
+}
+
+/// A signal from the `rawSignals` array of the newer on-device timeline export. Only position
+/// signals are of interest.
+#[derive(Deserialize, Debug)]
+struct RawSignal {
+    position: Option<Position>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Position {
+    #[serde(rename = "LatLng")]
+    lat_lng: Option<String>,
+    accuracy_meters: Option<f64>,
+    altitude_meters: Option<f64>,
+    speed_meters_per_second: Option<f64>,
+    timestamp: Option<String>,
+}
+
+/// Converts a coordinate in degrees multiplied by 10^7 to a decimal.
+///
+/// Some exports contain coordinates that overflowed a signed 32-bit integer, which are corrected
+/// here.
+fn e7(value: i64, limit: i64) -> BigDecimal {
+    let value = if value > limit * 10_000_000 {
+        value - (1 << 32)
+    } else {
+        value
+    };
+
+    BigDecimal::new(BigInt::from(value), 7)
+}
+
+/// Parses a coordinate pair formatted as either `"12.3456789°, -98.7654321°"` or
+/// `"geo:12.3456789,-98.7654321"`.
+fn parse_lat_lng(value: &str) -> Option<(BigDecimal, BigDecimal)> {
+    let value = value.trim();
+    let value = value.strip_prefix("geo:").unwrap_or(value);
+    let (latitude, longitude) = value.split_once(',')?;
+
+    let parse = |value: &str| BigDecimal::from_str(value.trim().trim_end_matches('°')).ok();
+
+    Some((parse(latitude)?, parse(longitude)?))
+}
+
+fn decimal(value: Option<f64>) -> Option<BigDecimal> {
+    value.and_then(|value| BigDecimal::try_from(value).ok())
+}
+
+fn location_point(location: &Location) -> Option<TrackPoint> {
+    let timestamp = match (&location.timestamp, &location.timestamp_ms) {
+        (Some(timestamp), _) => parse_timestamp(timestamp)?,
+        (None, Some(timestamp_ms)) => OffsetDateTime::from_unix_timestamp_nanos(
+            i128::from(timestamp_ms.parse::<i64>().ok()?) * 1_000_000,
+        )
+        .ok()?,
+        (None, None) => return None,
+    };
+
+    Some(TrackPoint {
+        timestamp,
+        latitude: e7(location.latitude_e7?, 90),
+        longitude: e7(location.longitude_e7?, 180),
+        altitude: location.altitude.map(BigDecimal::from),
+        speed: location.velocity.map(BigDecimal::from),
+        bearing: location.heading.map(BigDecimal::from),
+        accuracy: location.accuracy.map(BigDecimal::from),
+    })
+}
+
+fn lat_lng_point(timestamp: Option<&str>, lat_lng: Option<&str>) -> Option<TrackPoint> {
+    let (latitude, longitude) = parse_lat_lng(lat_lng?)?;
+
+    Some(TrackPoint {
+        timestamp: parse_timestamp(timestamp?)?,
+        latitude,
+        longitude,
+        altitude: None,
+        speed: None,
+        bearing: None,
+        accuracy: None,
+    })
+}
+
+/// Expands a semantic segment into the points it contains, in order. Visits contribute their
+/// location at the start of the visit, and activities their start and end locations.
+fn segment_points(segment: Segment) -> Vec<Option<TrackPoint>> {
+    let mut points: Vec<Option<TrackPoint>> = segment
+        .timeline_path
+        .into_iter()
+        .map(|point| lat_lng_point(point.time.as_deref(), point.point.as_deref()))
+        .collect();
+
+    if let Some(visit) = segment.visit {
+        points.push(lat_lng_point(
+            segment.start_time.as_deref(),
+            visit
+                .top_candidate
+                .and_then(|candidate| candidate.place_location)
+                .and_then(|location| location.lat_lng)
+                .as_deref(),
+        ));
+    }
+
+    if let Some(activity) = segment.activity {
+        points.push(lat_lng_point(
+            segment.start_time.as_deref(),
+            activity
+                .start
+                .and_then(|location| location.lat_lng)
+                .as_deref(),
+        ));
+        points.push(lat_lng_point(
+            segment.end_time.as_deref(),
+            activity
+                .end
+                .and_then(|location| location.lat_lng)
+                .as_deref(),
+        ));
+    }
+
+    points
+}
+
+/// Converts a raw signal to a point. Signals other than positions, such as Wi-Fi scans, contain no
+/// points.
+fn raw_signal_points(signal: RawSignal) -> Vec<Option<TrackPoint>> {
+    let Some(position) = signal.position else {
+        return Vec::new();
+    };
+
+    let mut point = lat_lng_point(position.timestamp.as_deref(), position.lat_lng.as_deref());
+
+    if let Some(point) = &mut point {
+        point.altitude = decimal(position.altitude_meters);
+        point.speed = decimal(position.speed_meters_per_second);
+        point.accuracy = decimal(position.accuracy_meters);
+    }
+
+    vec![point]
+}
+
+#[derive(Clone, Copy)]
+enum RecordKind {
+    Location,
+    Segment,
+    RawSignal,
+}
+
+struct Document<'a, F> {
+    callback: &'a mut F,
+}
+
+struct Records<'a, F> {
+    callback: &'a mut F,
+    kind: RecordKind,
+}
+
+impl<'de, F: FnMut(Option<TrackPoint>) -> anyhow::Result<()>> Visitor<'de> for Document<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a location history export")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            let kind = match key.as_str() {
+                "locations" => RecordKind::Location,
+                "semanticSegments" => RecordKind::Segment,
+                "rawSignals" => RecordKind::RawSignal,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                    continue;
+                }
+            };
+
+            map.next_value_seed(Records {
+                callback: &mut *self.callback,
+                kind,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de, F: FnMut(Option<TrackPoint>) -> anyhow::Result<()>> DeserializeSeed<'de>
+    for Records<'_, F>
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(Option<TrackPoint>) -> anyhow::Result<()>> Visitor<'de> for Records<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of location records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        // Each record is read individually, so that a single malformed record is rejected rather
+        // than failing the entire import.
+        while let Some(value) = seq.next_element::<serde_json::Value>()? {
+            let points = match self.kind {
+                RecordKind::Location => vec![
+                    serde_json::from_value(value)
+                        .ok()
+                        .as_ref()
+                        .and_then(location_point),
+                ],
+                RecordKind::Segment => {
+                    serde_json::from_value(value).map_or_else(|_| vec![None], segment_points)
+                }
+                RecordKind::RawSignal => {
+                    serde_json::from_value(value).map_or_else(|_| vec![None], raw_signal_points)
+                }
+            };
+
+            for point in points {
+                (self.callback)(point).map_err(A::Error::custom)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Streams the points of a Google Takeout location history export, without reading the entire
+/// file into memory.
+///
+/// Both the original `Records.json` export and the newer on-device timeline export containing
+/// `semanticSegments` and `rawSignals` are supported. The callback is called for each point in
+/// the order it appears in the file, or with `None` for a record that could not be read. Points
+/// are only approximately sorted by timestamp, particularly in the newer format.
+pub fn read<R: Read, F: FnMut(Option<TrackPoint>) -> anyhow::Result<()>>(
+    reader: R,
+    mut callback: F,
+) -> anyhow::Result<()> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+
+    deserializer.deserialize_map(Document {
+        callback: &mut callback,
+    })?;
+    deserializer.end()?;
+
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use serde::Deserialize;
+
+/// The mean radius of the Earth in meters.
+pub const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// Calculates the great-circle distance in meters between two points using the haversine formula.
+#[must_use]
+pub fn distance(latitude1: f64, longitude1: f64, latitude2: f64, longitude2: f64) -> f64 {
+    let latitude1 = latitude1.to_radians();
+    let latitude2 = latitude2.to_radians();
+    let delta_latitude = latitude2 - latitude1;
+    let delta_longitude = (longitude2 - longitude1).to_radians();
+
+    let a = (delta_latitude / 2.0).sin().powi(2)
+        + latitude1.cos() * latitude2.cos() * (delta_longitude / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS_METERS * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// Calculates the initial bearing in degrees, in the range [0, 360), from the first point towards
+/// the second.
+#[must_use]
+pub fn bearing(latitude1: f64, longitude1: f64, latitude2: f64, longitude2: f64) -> f64 {
+    let latitude1 = latitude1.to_radians();
+    let latitude2 = latitude2.to_radians();
+    let delta_longitude = (longitude2 - longitude1).to_radians();
+
+    let y = delta_longitude.sin() * latitude2.cos();
+    let x = latitude1.cos() * latitude2.sin()
+        - latitude1.sin() * latitude2.cos() * delta_longitude.cos();
+
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Calculates the distance in meters from a point to the segment between two others, treating
+/// the surface of the Earth as flat in the vicinity of the segment. Points are given as latitude
+/// and longitude.
+#[must_use]
+pub fn segment_distance(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    // Project onto a plane tangent at the start of the segment, in meters.
+    let scale = EARTH_RADIUS_METERS.to_radians();
+    let longitude_scale = scale * start.0.to_radians().cos();
+
+    let project = |(latitude, longitude): (f64, f64)| {
+        let mut delta_longitude = longitude - start.1;
+
+        if delta_longitude > 180.0 {
+            delta_longitude -= 360.0;
+        } else if delta_longitude < -180.0 {
+            delta_longitude += 360.0;
+        }
+
+        (
+            delta_longitude * longitude_scale,
+            (latitude - start.0) * scale,
+        )
+    };
+
+    let (x, y) = project(point);
+    let (end_x, end_y) = project(end);
+    let length_squared = end_x * end_x + end_y * end_y;
+
+    let fraction = if length_squared > 0.0 {
+        ((x * end_x + y * end_y) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (x - fraction * end_x).hypot(y - fraction * end_y)
+}
+
+/// Calculates the half-extents in degrees of latitude and longitude of a box containing every
+/// point within the given distance in meters of a point at the given latitude. The longitude
+/// extent is 180 degrees if the circle reaches either pole.
+#[must_use]
+pub fn radius_extent(latitude: f64, radius: f64) -> (f64, f64) {
+    // Allow for rounding, as points on the circle must not fall outside of the box.
+    const MARGIN: f64 = 1e-6;
+
+    let angle = radius / EARTH_RADIUS_METERS;
+    let latitude_extent = angle.to_degrees() + MARGIN;
+
+    if latitude.abs() + latitude_extent >= 90.0 {
+        return (latitude_extent, 180.0);
+    }
+
+    let ratio = angle.sin() / latitude.to_radians().cos();
+
+    if ratio >= 1.0 {
+        return (latitude_extent, 180.0);
+    }
+
+    (latitude_extent, ratio.asin().to_degrees() + MARGIN)
+}
+
+/// A closed ring of (longitude, latitude) positions.
+pub type Ring = Vec<(f64, f64)>;
+
+/// A polygon, consisting of an exterior ring and any holes within it.
+pub type Polygon = (Ring, Vec<Ring>);
+
+/// An area on the surface of the Earth, consisting of any number of polygons. Edges are treated as
+/// straight lines in longitude and latitude, taking the shorter way around the Earth, so the
+/// longitudes of a polygon crossing the antimeridian may extend beyond 180 degrees.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Area {
+    pub polygons: Vec<Polygon>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum GeoJson {
+    Feature {
+        geometry: Box<GeoJson>,
+    },
+    MultiPolygon {
+        coordinates: Vec<Vec<Vec<Vec<f64>>>>,
+    },
+    Polygon {
+        coordinates: Vec<Vec<Vec<f64>>>,
+    },
+}
+
+impl Area {
+    /// Parses a bounding box in the form `minLon,minLat,maxLon,maxLat`. A box whose minimum
+    /// longitude exceeds its maximum longitude crosses the antimeridian.
+    pub fn from_bbox(value: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid bbox, expected minLon,minLat,maxLon,maxLat: {value}");
+
+        let coordinates = value
+            .split(',')
+            .map(|coordinate| coordinate.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+
+        let &[min_longitude, min_latitude, max_longitude, max_latitude] = coordinates.as_slice()
+        else {
+            return Err(invalid());
+        };
+
+        for (longitude, latitude) in [(min_longitude, min_latitude), (max_longitude, max_latitude)]
+        {
+            validate_position(longitude, latitude)?;
+        }
+
+        if min_latitude > max_latitude {
+            return Err(invalid());
+        }
+
+        let rectangle = |min_longitude: f64, max_longitude: f64| {
+            vec![
+                (min_longitude, min_latitude),
+                (max_longitude, min_latitude),
+                (max_longitude, max_latitude),
+                (min_longitude, max_latitude),
+                (min_longitude, min_latitude),
+            ]
+        };
+
+        let polygons = if min_longitude > max_longitude {
+            vec![
+                (rectangle(min_longitude, 180.0), Vec::new()),
+                (rectangle(-180.0, max_longitude), Vec::new()),
+            ]
+        } else {
+            vec![(rectangle(min_longitude, max_longitude), Vec::new())]
+        };
+
+        Ok(Self { polygons })
+    }
+
+    /// Parses a `GeoJSON` `Polygon` or `MultiPolygon` geometry, optionally wrapped in a `Feature`.
+    /// The first ring of each polygon is its exterior, and any others are holes within it. Edges
+    /// spanning more than 180 degrees of longitude cross the antimeridian.
+    pub fn from_geojson(body: &[u8]) -> Result<Self, String> {
+        let mut geometry: GeoJson = serde_json::from_slice(body)
+            .map_err(|e| format!("Failed to parse GeoJSON geometry: {e}"))?;
+
+        let polygons = loop {
+            match geometry {
+                GeoJson::Feature { geometry: inner } => geometry = *inner,
+                GeoJson::MultiPolygon { coordinates } => break coordinates,
+                GeoJson::Polygon { coordinates } => break vec![coordinates],
+            }
+        };
+
+        let polygons = polygons
+            .into_iter()
+            .map(|polygon| {
+                let mut rings = polygon.into_iter();
+
+                let exterior = rings
+                    .next()
+                    .ok_or_else(|| "Each polygon must have an exterior ring".to_string())?;
+
+                Ok((
+                    parse_ring(exterior)?,
+                    rings.map(parse_ring).collect::<Result<_, _>>()?,
+                ))
+            })
+            .collect::<Result<Vec<Polygon>, String>>()?;
+
+        if polygons.is_empty() {
+            return Err("The geometry must contain at least one polygon".to_string());
+        }
+
+        Ok(Self { polygons })
+    }
+
+    /// Returns whether a position lies within the area.
+    #[must_use]
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        self.polygons.iter().any(|(exterior, holes)| {
+            // Polygons crossing the antimeridian may contain the position one turn to the east or
+            // west of its longitude.
+            [-360.0, 0.0, 360.0].into_iter().any(|shift| {
+                let within = |ring: &Ring| ring_contains(ring, latitude, longitude + shift);
+
+                within(exterior) && !holes.iter().any(within)
+            })
+        })
+    }
+
+    /// Calculates the distance in meters from a position to the nearest edge of the area.
+    #[must_use]
+    pub fn boundary_distance(&self, latitude: f64, longitude: f64) -> f64 {
+        self.polygons
+            .iter()
+            .flat_map(|(exterior, holes)| std::iter::once(exterior).chain(holes))
+            .flat_map(|ring| ring.windows(2))
+            .map(|edge| {
+                segment_distance(
+                    (latitude, longitude),
+                    (edge[0].1, edge[0].0),
+                    (edge[1].1, edge[1].0),
+                )
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+fn validate_position(longitude: f64, latitude: f64) -> Result<(), String> {
+    if !(-180.0..=180.0).contains(&longitude) || !(-90.0..=90.0).contains(&latitude) {
+        return Err(format!("Invalid position: {longitude},{latitude}"));
+    }
+
+    Ok(())
+}
+
+fn parse_ring(positions: Vec<Vec<f64>>) -> Result<Ring, String> {
+    let ring = positions
+        .into_iter()
+        .map(|position| match position.as_slice() {
+            &[longitude, latitude, ..] => {
+                validate_position(longitude, latitude)?;
+                Ok((longitude, latitude))
+            }
+            _ => Err("Each position must have a longitude and latitude".to_string()),
+        })
+        .collect::<Result<Ring, String>>()?;
+
+    if ring.len() < 4 || ring.first() != ring.last() {
+        return Err("Each ring must be closed and have at least four positions".to_string());
+    }
+
+    unwrap_ring(&ring)
+}
+
+/// Adjusts the longitudes of a ring so that no edge spans more than 180 degrees, continuing past
+/// the antimeridian where an edge crosses it.
+fn unwrap_ring(ring: &Ring) -> Result<Ring, String> {
+    let mut unwrapped: Ring = Vec::with_capacity(ring.len());
+
+    for &(longitude, latitude) in ring {
+        let longitude = match unwrapped.last() {
+            Some(&(previous, _)) => {
+                previous + (longitude - previous + 180.0).rem_euclid(360.0) - 180.0
+            }
+            None => longitude,
+        };
+
+        unwrapped.push((longitude, latitude));
+    }
+
+    if unwrapped.first() != unwrapped.last() {
+        return Err("Rings must not encircle a pole".to_string());
+    }
+
+    Ok(unwrapped)
+}
+
+/// Returns whether a position lies within a ring, using the even-odd rule.
+fn ring_contains(ring: &Ring, latitude: f64, longitude: f64) -> bool {
+    ring.windows(2).fold(false, |inside, edge| {
+        let ((longitude1, latitude1), (longitude2, latitude2)) = (edge[0], edge[1]);
+
+        if (latitude1 > latitude) != (latitude2 > latitude)
+            && longitude
+                < longitude1
+                    + (latitude - latitude1) * (longitude2 - longitude1) / (latitude2 - latitude1)
+        {
+            !inside
+        } else {
+            inside
+        }
+    })
+}
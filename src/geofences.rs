@@ -0,0 +1,110 @@
+use anyhow::Context;
+use sqlx::PgConnection;
+
+use crate::geo::{self, Area};
+use crate::models::{Geofence, GeofenceEvent, GeofenceEventKind, Report};
+
+/// The area covered by a geofence.
+pub enum Shape {
+    Circle {
+        latitude: f64,
+        longitude: f64,
+        radius: f64,
+    },
+    Area(Area),
+}
+
+impl Shape {
+    pub fn from_geofence(geofence: &Geofence) -> anyhow::Result<Self> {
+        match (
+            geofence.latitude,
+            geofence.longitude,
+            geofence.radius,
+            &geofence.geometry,
+        ) {
+            (Some(latitude), Some(longitude), Some(radius), None) => Ok(Self::Circle {
+                latitude,
+                longitude,
+                radius,
+            }),
+            (None, None, None, Some(geometry)) => Area::from_geojson(geometry.as_bytes())
+                .map(Self::Area)
+                .map_err(anyhow::Error::msg),
+            _ => anyhow::bail!("The geofence must be either a circle or a polygon"),
+        }
+    }
+
+    /// Calculates the distance in meters from a position to the shape, which is zero within it.
+    #[must_use]
+    pub fn distance(&self, latitude: f64, longitude: f64) -> f64 {
+        match self {
+            Self::Circle {
+                latitude: center_latitude,
+                longitude: center_longitude,
+                radius,
+            } => (geo::distance(*center_latitude, *center_longitude, latitude, longitude) - radius)
+                .max(0.0),
+            Self::Area(area) => {
+                if area.contains(latitude, longitude) {
+                    0.0
+                } else {
+                    area.boundary_distance(latitude, longitude)
+                }
+            }
+        }
+    }
+}
+
+/// Evaluates a newly submitted report against the geofences of its device, recording an event for
+/// each geofence it enters or exits.
+///
+/// A device enters a geofence upon reporting a position within it, but only exits once it reports
+/// a position beyond the hysteresis distance of the boundary, so that jitter near the boundary does
+/// not produce a flurry of events. Reports older than the last report evaluated against a geofence
+/// are submitted late, and do not affect it.
+#[tracing::instrument(name = "Evaluate geofences", skip(db, report))]
+pub async fn evaluate(db: &mut PgConnection, report: &Report) -> anyhow::Result<()> {
+    let geofences = Geofence::lock_by_device(&mut *db, report.device_id)
+        .await
+        .context("Failed to retrieve the geofences of the device")?;
+
+    let (latitude, longitude) = report.coordinates();
+
+    for geofence in geofences {
+        if geofence
+            .state_timestamp
+            .is_some_and(|timestamp| report.timestamp < timestamp)
+        {
+            continue;
+        }
+
+        let distance = Shape::from_geofence(&geofence)
+            .with_context(|| format!("Failed to parse the geofence {}", geofence.id))?
+            .distance(latitude, longitude);
+
+        let inside = if geofence.inside {
+            distance <= geofence.hysteresis
+        } else {
+            distance <= 0.0
+        };
+
+        if inside != geofence.inside {
+            let kind = if inside {
+                GeofenceEventKind::Enter
+            } else {
+                GeofenceEventKind::Exit
+            };
+
+            GeofenceEvent::create(&mut *db, &geofence, report, kind)
+                .await
+                .context("Failed to record the geofence event")?;
+        }
+
+        geofence
+            .update_state(&mut *db, inside, report.timestamp)
+            .await
+            .context("Failed to update the state of the geofence")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+
+use serde::Serialize;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::geo;
+use crate::history::Fix;
+use crate::models::Report;
+
+/// A period during which two devices were within a given distance of each other.
+#[derive(Serialize, Debug)]
+pub struct ColocationInterval {
+    #[serde(with = "time::serde::iso8601")]
+    pub start: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub end: OffsetDateTime,
+    /// The smallest distance between the devices within the interval, in meters.
+    pub min_distance: f64,
+}
+
+/// The number of reports fetched from the database at a time for each device.
+const PAGE_SIZE: i64 = 1000;
+
+/// The reports of a single device, read one ahead so that its position may be interpolated at any
+/// timestamp between its reports. Reports are fetched in pages, so that no connection is held
+/// while the reports of the other device are read.
+struct Track<'a> {
+    db: &'a PgPool,
+    device_id: Uuid,
+    since: OffsetDateTime,
+    until: OffsetDateTime,
+    page: VecDeque<Fix>,
+    after: Option<(OffsetDateTime, Uuid)>,
+    exhausted: bool,
+    previous: Option<Fix>,
+    next: Option<Fix>,
+}
+
+impl<'a> Track<'a> {
+    async fn new(
+        db: &'a PgPool,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+    ) -> Result<Self, sqlx::Error> {
+        let mut track = Self {
+            db,
+            device_id,
+            since,
+            until,
+            page: VecDeque::new(),
+            after: None,
+            exhausted: false,
+            previous: None,
+            next: None,
+        };
+
+        track.next = track.fetch().await?;
+
+        Ok(track)
+    }
+
+    /// Returns the next report of the device, fetching another page once the current one is used.
+    async fn fetch(&mut self) -> Result<Option<Fix>, sqlx::Error> {
+        if self.page.is_empty() && !self.exhausted {
+            let reports = Report::find_page(
+                self.db,
+                self.device_id,
+                self.since,
+                self.until,
+                None,
+                self.after,
+                PAGE_SIZE,
+            )
+            .await?;
+
+            if let Some(report) = reports.last() {
+                self.after = Some((report.timestamp, report.id));
+            }
+
+            self.exhausted = reports.len() < usize::try_from(PAGE_SIZE).unwrap_or(usize::MAX);
+            self.page.extend(reports.iter().map(Fix::from));
+        }
+
+        Ok(self.page.pop_front())
+    }
+
+    async fn advance(&mut self) -> Result<(), sqlx::Error> {
+        self.previous = self.next.take();
+        self.next = self.fetch().await?;
+
+        Ok(())
+    }
+
+    /// Returns the position of the device at the given timestamp, which must not precede the
+    /// previous report. The position is unknown if the surrounding reports are too far apart.
+    fn position(&self, timestamp: OffsetDateTime, max_gap: Duration) -> Option<(f64, f64)> {
+        let previous = self.previous?;
+
+        if previous.timestamp == timestamp {
+            return Some((previous.latitude, previous.longitude));
+        }
+
+        let next = self.next?;
+
+        (next.timestamp - previous.timestamp <= max_gap)
+            .then(|| previous.interpolate(&next, timestamp))
+    }
+}
+
+/// Finds the intervals during which two devices were within the given distance of each other.
+///
+/// As the reports of the devices are not simultaneous, the position of each device is
+/// interpolated at the timestamp of every report of either device, provided its own reports on
+/// either side are no more than `max_gap` apart. An interval ends at the first such timestamp at
+/// which the devices are further apart or the position of either is unknown.
+pub async fn find_colocations(
+    db: &PgPool,
+    (first, second): (Uuid, Uuid),
+    (since, until): (OffsetDateTime, OffsetDateTime),
+    distance: f64,
+    max_gap: Duration,
+) -> Result<Vec<ColocationInterval>, sqlx::Error> {
+    let mut first = Track::new(db, first, since, until).await?;
+    let mut second = Track::new(db, second, since, until).await?;
+
+    let mut intervals = Vec::new();
+    let mut current: Option<ColocationInterval> = None;
+
+    loop {
+        let timestamp = match (first.next, second.next) {
+            (Some(a), Some(b)) if a.timestamp <= b.timestamp => {
+                first.advance().await?;
+                a.timestamp
+            }
+            (Some(a), None) => {
+                first.advance().await?;
+                a.timestamp
+            }
+            (_, Some(b)) => {
+                second.advance().await?;
+                b.timestamp
+            }
+            (None, None) => break,
+        };
+
+        let separation = first
+            .position(timestamp, max_gap)
+            .zip(second.position(timestamp, max_gap))
+            .map(|((latitude1, longitude1), (latitude2, longitude2))| {
+                geo::distance(latitude1, longitude1, latitude2, longitude2)
+            })
+            .filter(|separation| *separation <= distance);
+
+        match (separation, &mut current) {
+            (Some(separation), Some(interval)) => {
+                interval.end = timestamp;
+                interval.min_distance = interval.min_distance.min(separation);
+            }
+            (Some(separation), None) => {
+                current = Some(ColocationInterval {
+                    start: timestamp,
+                    end: timestamp,
+                    min_distance: separation,
+                });
+            }
+            (None, _) => intervals.extend(current.take()),
+        }
+    }
+
+    intervals.extend(current.take());
+
+    Ok(intervals)
+}
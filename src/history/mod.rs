@@ -0,0 +1,63 @@
+use time::OffsetDateTime;
+
+use crate::models::Report;
+
+pub mod colocation;
+pub mod proximity;
+pub mod simplify;
+pub mod statistics;
+pub mod stays;
+pub mod summaries;
+pub mod trips;
+pub mod visits;
+
+/// The timestamp and position of a report in degrees.
+#[derive(Clone, Copy, Debug)]
+pub struct Fix {
+    pub timestamp: OffsetDateTime,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Fix {
+    /// Returns the position at the given timestamp, which should lie between the timestamps of
+    /// this fix and the next, assuming constant motion between them.
+    #[must_use]
+    pub fn interpolate(&self, next: &Fix, timestamp: OffsetDateTime) -> (f64, f64) {
+        let duration = (next.timestamp - self.timestamp).as_seconds_f64();
+
+        if duration <= 0.0 {
+            return (self.latitude, self.longitude);
+        }
+
+        let fraction = ((timestamp - self.timestamp).as_seconds_f64() / duration).clamp(0.0, 1.0);
+
+        // Take the shorter way around when crossing the antimeridian.
+        let mut delta_longitude = next.longitude - self.longitude;
+
+        if delta_longitude > 180.0 {
+            delta_longitude -= 360.0;
+        } else if delta_longitude < -180.0 {
+            delta_longitude += 360.0;
+        }
+
+        let longitude = self.longitude + delta_longitude * fraction;
+
+        (
+            self.latitude + (next.latitude - self.latitude) * fraction,
+            (longitude + 180.0).rem_euclid(360.0) - 180.0,
+        )
+    }
+}
+
+impl From<&Report> for Fix {
+    fn from(report: &Report) -> Self {
+        let (latitude, longitude) = report.coordinates();
+
+        Self {
+            timestamp: report.timestamp,
+            latitude,
+            longitude,
+        }
+    }
+}
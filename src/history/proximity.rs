@@ -0,0 +1,75 @@
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::geo;
+use crate::models::Report;
+
+/// A period during which consecutive reports of a device were within the radius of a point.
+#[derive(Serialize, Debug)]
+pub struct ProximityInterval {
+    /// The timestamp of the first report within the radius.
+    #[serde(with = "time::serde::iso8601")]
+    pub entered: OffsetDateTime,
+    /// The timestamp of the last report within the radius.
+    #[serde(with = "time::serde::iso8601")]
+    pub exited: OffsetDateTime,
+    /// The smallest distance from the point of any report within the interval, in meters.
+    pub min_distance: f64,
+    /// The number of reports within the interval.
+    pub reports: u64,
+}
+
+/// Finds the intervals during which reports, given in ascending order of timestamp, were within
+/// the radius of a point. An interval ends with the first report outside of the radius.
+pub struct ProximitySearch {
+    latitude: f64,
+    longitude: f64,
+    radius: f64,
+    current: Option<ProximityInterval>,
+    intervals: Vec<ProximityInterval>,
+}
+
+impl ProximitySearch {
+    #[must_use]
+    pub fn new(latitude: f64, longitude: f64, radius: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            radius,
+            current: None,
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, report: &Report) {
+        let (latitude, longitude) = report.coordinates();
+        let distance = geo::distance(self.latitude, self.longitude, latitude, longitude);
+
+        if distance > self.radius {
+            self.intervals.extend(self.current.take());
+            return;
+        }
+
+        match &mut self.current {
+            Some(interval) => {
+                interval.exited = report.timestamp;
+                interval.min_distance = interval.min_distance.min(distance);
+                interval.reports += 1;
+            }
+            None => {
+                self.current = Some(ProximityInterval {
+                    entered: report.timestamp,
+                    exited: report.timestamp,
+                    min_distance: distance,
+                    reports: 1,
+                });
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn finish(mut self) -> Vec<ProximityInterval> {
+        self.intervals.extend(self.current.take());
+        self.intervals
+    }
+}
@@ -0,0 +1,117 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use time::Duration;
+
+use crate::geo;
+use crate::history::Fix;
+
+/// Reports separated by more than this are treated as separate trips, whose first and last
+/// reports are always kept.
+const SEGMENT_GAP: Duration = Duration::minutes(5);
+
+/// A run of fixes between two kept fixes, along with the fix furthest from the line between them.
+struct Span {
+    start: usize,
+    end: usize,
+    furthest: usize,
+    distance: f64,
+}
+
+impl Span {
+    fn new(fixes: &[Fix], start: usize, end: usize) -> Option<Self> {
+        let position = |fix: &Fix| (fix.latitude, fix.longitude);
+
+        (start + 1..end)
+            .map(|index| {
+                let distance = geo::segment_distance(
+                    position(&fixes[index]),
+                    position(&fixes[start]),
+                    position(&fixes[end]),
+                );
+
+                (index, distance)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(furthest, distance)| Self {
+                start,
+                end,
+                furthest,
+                distance,
+            })
+    }
+}
+
+impl PartialEq for Span {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Span {}
+
+impl PartialOrd for Span {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Span {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.total_cmp(&other.distance)
+    }
+}
+
+/// Simplifies a track given in ascending order of timestamp, returning the indices of the fixes to
+/// keep in ascending order.
+///
+/// The track is first divided into trips wherever consecutive fixes are far apart in time, and the
+/// first and last fix of each trip are kept. Using the Douglas-Peucker algorithm, the fix furthest
+/// from the simplified track is then repeatedly added until every fix is within `tolerance` meters
+/// of it or `max_points` fixes have been kept, whichever comes first. As the ends of each trip are
+/// always kept, more than `max_points` fixes may be returned.
+#[must_use]
+pub fn simplify(fixes: &[Fix], tolerance: Option<f64>, max_points: Option<usize>) -> Vec<usize> {
+    let mut kept = vec![false; fixes.len()];
+    let mut spans = BinaryHeap::new();
+    let mut count = 0;
+
+    let mut start = 0;
+
+    for end in 0..fixes.len() {
+        let last = fixes
+            .get(end + 1)
+            .is_none_or(|next| next.timestamp - fixes[end].timestamp > SEGMENT_GAP);
+
+        if last {
+            for index in [start, end] {
+                if !kept[index] {
+                    kept[index] = true;
+                    count += 1;
+                }
+            }
+
+            spans.extend(Span::new(fixes, start, end));
+            start = end + 1;
+        }
+    }
+
+    while let Some(span) = spans.pop() {
+        if tolerance.is_some_and(|tolerance| span.distance <= tolerance)
+            || max_points.is_some_and(|max_points| count >= max_points)
+        {
+            break;
+        }
+
+        kept[span.furthest] = true;
+        count += 1;
+
+        spans.extend(Span::new(fixes, span.start, span.furthest));
+        spans.extend(Span::new(fixes, span.furthest, span.end));
+    }
+
+    kept.iter()
+        .enumerate()
+        .filter_map(|(index, kept)| kept.then_some(index))
+        .collect()
+}
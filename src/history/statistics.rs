@@ -0,0 +1,121 @@
+use bigdecimal::ToPrimitive;
+use serde::Serialize;
+use time::Duration;
+
+use crate::geo;
+use crate::history::Fix;
+use crate::models::Report;
+
+/// Travel between consecutive reports slower than this, in meters per second, is not considered
+/// movement, as the reported position drifts even while stationary.
+const MOVING_SPEED: f64 = 0.5;
+
+/// Changes in altitude smaller than this, in meters, are ignored as noise.
+const ELEVATION_THRESHOLD: f64 = 5.0;
+
+/// Accumulates statistics over reports given in ascending order of timestamp.
+#[derive(Clone, Debug, Default)]
+pub struct Statistics {
+    first: Option<Fix>,
+    previous: Option<Fix>,
+    reports: u64,
+    distance: f64,
+    moving_distance: f64,
+    moving_time: Duration,
+    max_speed: f64,
+    /// The altitude from which the next change in elevation is measured.
+    reference_altitude: Option<f64>,
+    ascent: f64,
+    descent: f64,
+}
+
+/// The statistics of a set of reports, with distances in meters, durations in seconds and speeds
+/// in meters per second. The average speed is that while moving.
+#[derive(Serialize, Debug)]
+pub struct StatisticsSummary {
+    pub reports: u64,
+    pub distance: f64,
+    pub duration: f64,
+    pub moving_time: f64,
+    pub average_speed: f64,
+    pub max_speed: f64,
+    pub ascent: f64,
+    pub descent: f64,
+}
+
+impl Statistics {
+    pub fn push(&mut self, report: &Report) {
+        let fix = Fix::from(report);
+
+        if let Some(previous) = self.previous {
+            let distance = geo::distance(
+                previous.latitude,
+                previous.longitude,
+                fix.latitude,
+                fix.longitude,
+            );
+            let elapsed = fix.timestamp - previous.timestamp;
+
+            self.distance += distance;
+
+            if elapsed.is_positive() && distance / elapsed.as_seconds_f64() >= MOVING_SPEED {
+                self.moving_distance += distance;
+                self.moving_time += elapsed;
+            }
+        }
+
+        self.first.get_or_insert(fix);
+        self.previous = Some(fix);
+        self.reports += 1;
+        self.max_speed = self
+            .max_speed
+            .max(report.speed.to_f64().unwrap_or_default());
+
+        let altitude = report.altitude.to_f64().unwrap_or_default();
+
+        match self.reference_altitude {
+            None => self.reference_altitude = Some(altitude),
+            Some(reference) if altitude - reference >= ELEVATION_THRESHOLD => {
+                self.ascent += altitude - reference;
+                self.reference_altitude = Some(altitude);
+            }
+            Some(reference) if reference - altitude >= ELEVATION_THRESHOLD => {
+                self.descent += reference - altitude;
+                self.reference_altitude = Some(altitude);
+            }
+            Some(_) => {}
+        }
+    }
+
+    #[must_use]
+    pub fn reports(&self) -> u64 {
+        self.reports
+    }
+
+    #[must_use]
+    pub fn summary(&self) -> StatisticsSummary {
+        let duration = self
+            .first
+            .zip(self.previous)
+            .map_or(Duration::ZERO, |(first, last)| {
+                last.timestamp - first.timestamp
+            });
+
+        let moving_time = self.moving_time.as_seconds_f64();
+
+        StatisticsSummary {
+            reports: self.reports,
+            distance: self.distance,
+            duration: duration.as_seconds_f64(),
+            moving_time,
+            average_speed: if moving_time > 0.0 {
+                self.moving_distance / moving_time
+            } else {
+                0.0
+            },
+            max_speed: self.max_speed,
+            ascent: self.ascent,
+            descent: self.descent,
+        }
+    }
+}
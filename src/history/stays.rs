@@ -0,0 +1,179 @@
+use bigdecimal::ToPrimitive;
+use serde::Serialize;
+use time::{Duration, OffsetDateTime};
+
+use crate::geo;
+use crate::history::Fix;
+use crate::models::Report;
+use crate::settings::StaySettings;
+
+/// A period during which a device remained near a position.
+#[derive(Serialize, Debug, Clone)]
+pub struct Stay {
+    /// The latitude of the centroid of the reports within the stay.
+    pub latitude: f64,
+    /// The longitude of the centroid of the reports within the stay.
+    pub longitude: f64,
+    /// The timestamp of the first report within the stay.
+    #[serde(with = "time::serde::iso8601")]
+    pub arrival: OffsetDateTime,
+    /// The timestamp of the last report within the stay.
+    #[serde(with = "time::serde::iso8601")]
+    pub departure: OffsetDateTime,
+    /// The duration of the stay in seconds.
+    pub duration: f64,
+    /// The number of reports within the stay.
+    pub reports: u64,
+}
+
+/// The thresholds used to detect stays.
+#[derive(Clone, Copy, Debug)]
+pub struct StayThresholds {
+    /// Reports further than this from the centroid of a stay end it.
+    pub radius: f64,
+    /// The minimum duration of a stay.
+    pub duration: Duration,
+    /// Reports with a worse accuracy than this are ignored.
+    pub max_accuracy: f64,
+}
+
+impl From<&StaySettings> for StayThresholds {
+    fn from(settings: &StaySettings) -> Self {
+        Self {
+            radius: f64::from(settings.radius_meters),
+            duration: settings.duration(),
+            max_accuracy: f64::from(settings.max_accuracy_meters),
+        }
+    }
+}
+
+/// The reports considered for a potential stay, weighted by their accuracy.
+struct Cluster {
+    /// Longitudes are accumulated relative to this, so that clusters may span the antimeridian.
+    reference_longitude: f64,
+    weight: f64,
+    latitude_sum: f64,
+    longitude_sum: f64,
+    arrival: OffsetDateTime,
+    departure: OffsetDateTime,
+    reports: u64,
+}
+
+impl Cluster {
+    fn new(fix: &Fix, weight: f64) -> Self {
+        Self {
+            reference_longitude: fix.longitude,
+            weight,
+            latitude_sum: fix.latitude * weight,
+            longitude_sum: 0.0,
+            arrival: fix.timestamp,
+            departure: fix.timestamp,
+            reports: 1,
+        }
+    }
+
+    fn push(&mut self, fix: &Fix, weight: f64) {
+        let mut delta_longitude = fix.longitude - self.reference_longitude;
+
+        if delta_longitude > 180.0 {
+            delta_longitude -= 360.0;
+        } else if delta_longitude < -180.0 {
+            delta_longitude += 360.0;
+        }
+
+        self.weight += weight;
+        self.latitude_sum += fix.latitude * weight;
+        self.longitude_sum += delta_longitude * weight;
+        self.departure = fix.timestamp;
+        self.reports += 1;
+    }
+
+    fn centroid(&self) -> (f64, f64) {
+        let longitude = self.reference_longitude + self.longitude_sum / self.weight;
+
+        (
+            self.latitude_sum / self.weight,
+            (longitude + 180.0).rem_euclid(360.0) - 180.0,
+        )
+    }
+}
+
+/// Detects stays from reports given in ascending order of timestamp.
+///
+/// Consecutive reports within the radius of their accuracy-weighted centroid form a cluster,
+/// which ends with the first report outside of the radius. Clusters lasting at least the minimum
+/// duration are stays. Reports with a worse accuracy than the maximum are ignored entirely, so
+/// that a single poor fix neither ends nor starts a stay.
+pub struct StayDetector {
+    thresholds: StayThresholds,
+    cluster: Option<Cluster>,
+    stays: Vec<Stay>,
+}
+
+impl StayDetector {
+    #[must_use]
+    pub fn new(thresholds: StayThresholds) -> Self {
+        Self {
+            thresholds,
+            cluster: None,
+            stays: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, report: &Report) {
+        let accuracy = report.accuracy.to_f64().unwrap_or(f64::INFINITY);
+
+        if accuracy > self.thresholds.max_accuracy {
+            return;
+        }
+
+        let fix = Fix::from(report);
+        let weight = accuracy.max(1.0).powi(-2);
+
+        if let Some(cluster) = &mut self.cluster {
+            let (latitude, longitude) = cluster.centroid();
+
+            if geo::distance(latitude, longitude, fix.latitude, fix.longitude)
+                <= self.thresholds.radius
+            {
+                cluster.push(&fix, weight);
+                return;
+            }
+        }
+
+        let cluster = self.cluster.replace(Cluster::new(&fix, weight));
+        self.end_cluster(cluster);
+    }
+
+    /// Returns the detected stays. A stay in progress at the last report is included if it has
+    /// already lasted the minimum duration.
+    #[must_use]
+    pub fn finish(mut self) -> Vec<Stay> {
+        let cluster = self.cluster.take();
+        self.end_cluster(cluster);
+        self.stays
+    }
+
+    fn end_cluster(&mut self, cluster: Option<Cluster>) {
+        let Some(cluster) = cluster else {
+            return;
+        };
+
+        let duration = cluster.departure - cluster.arrival;
+
+        if duration < self.thresholds.duration {
+            return;
+        }
+
+        let (latitude, longitude) = cluster.centroid();
+
+        self.stays.push(Stay {
+            latitude,
+            longitude,
+            arrival: cluster.arrival,
+            departure: cluster.departure,
+            duration: duration.as_seconds_f64(),
+            reports: cluster.reports,
+        });
+    }
+}
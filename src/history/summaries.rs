@@ -0,0 +1,75 @@
+use time::OffsetDateTime;
+
+use crate::history::Fix;
+use crate::history::statistics::Statistics;
+use crate::history::stays::{StayDetector, StayThresholds};
+use crate::history::trips::{Bounds, TripSegmenter, TripThresholds};
+use crate::models::Report;
+
+/// The activity of a device over a period.
+#[derive(Debug)]
+pub struct Activity {
+    pub reports: u64,
+    pub distance: f64,
+    pub moving_time: f64,
+    pub trips: usize,
+    pub stays: usize,
+    pub first_report: Option<OffsetDateTime>,
+    pub last_report: Option<OffsetDateTime>,
+    pub bounds: Option<Bounds>,
+}
+
+/// Summarizes the activity of a device from reports given in ascending order of timestamp.
+pub struct ActivitySummarizer {
+    statistics: Statistics,
+    segmenter: TripSegmenter,
+    detector: StayDetector,
+    first_report: Option<OffsetDateTime>,
+    last_report: Option<OffsetDateTime>,
+    bounds: Option<Bounds>,
+}
+
+impl ActivitySummarizer {
+    #[must_use]
+    pub fn new(trip_thresholds: TripThresholds, stay_thresholds: StayThresholds) -> Self {
+        Self {
+            statistics: Statistics::default(),
+            segmenter: TripSegmenter::new(trip_thresholds),
+            detector: StayDetector::new(stay_thresholds),
+            first_report: None,
+            last_report: None,
+            bounds: None,
+        }
+    }
+
+    pub fn push(&mut self, report: &Report) {
+        let fix = Fix::from(report);
+
+        self.statistics.push(report);
+        self.segmenter.push(report);
+        self.detector.push(report);
+        self.first_report.get_or_insert(report.timestamp);
+        self.last_report = Some(report.timestamp);
+
+        match &mut self.bounds {
+            Some(bounds) => bounds.extend(&fix),
+            None => self.bounds = Some(Bounds::new(&fix)),
+        }
+    }
+
+    #[must_use]
+    pub fn finish(self) -> Activity {
+        let statistics = self.statistics.summary();
+
+        Activity {
+            reports: statistics.reports,
+            distance: statistics.distance,
+            moving_time: statistics.moving_time,
+            trips: self.segmenter.finish().len(),
+            stays: self.detector.finish().len(),
+            first_report: self.first_report,
+            last_report: self.last_report,
+            bounds: self.bounds,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use serde::Serialize;
+use time::{Duration, OffsetDateTime};
+
+use crate::geo;
+use crate::history::Fix;
+use crate::history::statistics::Statistics;
+use crate::models::Report;
+
+/// A continuous period of travel by a device.
+#[derive(Clone, Debug)]
+pub struct Trip {
+    pub start: Fix,
+    pub end: Fix,
+    pub bounds: Bounds,
+    pub statistics: Statistics,
+}
+
+impl Trip {
+    fn new(fix: Fix, report: &Report) -> Self {
+        let mut statistics = Statistics::default();
+        statistics.push(report);
+
+        Self {
+            start: fix,
+            end: fix,
+            bounds: Bounds::new(&fix),
+            statistics,
+        }
+    }
+
+    fn push(&mut self, fix: Fix, report: &Report) {
+        self.end = fix;
+        self.bounds.extend(&fix);
+        self.statistics.push(report);
+    }
+}
+
+/// The smallest bounding box containing a set of positions, serialized as
+/// `[minLon, minLat, maxLon, maxLat]`.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(into = "[f64; 4]")]
+pub struct Bounds {
+    pub min_latitude: f64,
+    pub min_longitude: f64,
+    pub max_latitude: f64,
+    pub max_longitude: f64,
+}
+
+impl Bounds {
+    #[must_use]
+    pub fn new(fix: &Fix) -> Self {
+        Self {
+            min_latitude: fix.latitude,
+            min_longitude: fix.longitude,
+            max_latitude: fix.latitude,
+            max_longitude: fix.longitude,
+        }
+    }
+
+    pub fn extend(&mut self, fix: &Fix) {
+        self.min_latitude = self.min_latitude.min(fix.latitude);
+        self.min_longitude = self.min_longitude.min(fix.longitude);
+        self.max_latitude = self.max_latitude.max(fix.latitude);
+        self.max_longitude = self.max_longitude.max(fix.longitude);
+    }
+}
+
+impl From<Bounds> for [f64; 4] {
+    fn from(bounds: Bounds) -> Self {
+        [
+            bounds.min_longitude,
+            bounds.min_latitude,
+            bounds.max_longitude,
+            bounds.max_latitude,
+        ]
+    }
+}
+
+/// The thresholds used to divide reports into trips.
+#[derive(Clone, Copy, Debug)]
+pub struct TripThresholds {
+    /// Consecutive reports further apart than this belong to separate trips.
+    pub gap: Duration,
+    /// A device remaining within `stationary_radius` of a position for this long has stopped.
+    pub stationary: Duration,
+    pub stationary_radius: f64,
+}
+
+/// A position at which the device may have stopped, along with the trip as it was upon arriving.
+struct Anchor {
+    fix: Fix,
+    trip: Option<Trip>,
+}
+
+/// Divides reports, given in ascending order of timestamp, into trips.
+///
+/// A trip ends when the next report is more than the gap away, or when the device stops, meaning
+/// that it remains within the stationary radius of a position for at least the stationary
+/// duration. A stopped trip ends upon arrival at that position, and the next trip starts with the
+/// first report outside of the radius. Reports while stopped belong to no trip, and trips
+/// consisting of a single report are discarded.
+pub struct TripSegmenter {
+    thresholds: TripThresholds,
+    previous: Option<OffsetDateTime>,
+    trip: Option<Trip>,
+    anchor: Option<Anchor>,
+    stopped: bool,
+    trips: Vec<Trip>,
+}
+
+impl TripSegmenter {
+    #[must_use]
+    pub fn new(thresholds: TripThresholds) -> Self {
+        Self {
+            thresholds,
+            previous: None,
+            trip: None,
+            anchor: None,
+            stopped: false,
+            trips: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, report: &Report) {
+        let fix = Fix::from(report);
+
+        if self
+            .previous
+            .is_some_and(|previous| fix.timestamp - previous > self.thresholds.gap)
+        {
+            let trip = self.trip.take();
+            self.end_trip(trip);
+            self.anchor = None;
+            self.stopped = false;
+        }
+
+        self.previous = Some(fix.timestamp);
+
+        let near_anchor = self.anchor.as_ref().is_some_and(|anchor| {
+            geo::distance(
+                anchor.fix.latitude,
+                anchor.fix.longitude,
+                fix.latitude,
+                fix.longitude,
+            ) <= self.thresholds.stationary_radius
+        });
+
+        if self.stopped {
+            if near_anchor {
+                return;
+            }
+
+            self.stopped = false;
+        }
+
+        match &mut self.trip {
+            Some(trip) => trip.push(fix, report),
+            None => self.trip = Some(Trip::new(fix, report)),
+        }
+
+        match &self.anchor {
+            Some(anchor) if near_anchor => {
+                if fix.timestamp - anchor.fix.timestamp >= self.thresholds.stationary {
+                    let trip = anchor.trip.clone();
+                    self.end_trip(trip);
+                    self.trip = None;
+                    self.stopped = true;
+                }
+            }
+            _ => {
+                self.anchor = Some(Anchor {
+                    fix,
+                    trip: self.trip.clone(),
+                });
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn finish(mut self) -> Vec<Trip> {
+        let trip = self.trip.take();
+        self.end_trip(trip);
+        self.trips
+    }
+
+    fn end_trip(&mut self, trip: Option<Trip>) {
+        self.trips
+            .extend(trip.filter(|trip| trip.statistics.reports() > 1));
+    }
+}
@@ -0,0 +1,145 @@
+use bigdecimal::ToPrimitive;
+use serde::Serialize;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::geo;
+use crate::history::stays::StayThresholds;
+use crate::models::{Place, Report};
+
+/// A period during which a device remained within a place.
+#[derive(Serialize, Debug)]
+pub struct Visit {
+    pub place_id: Uuid,
+    pub name: String,
+    #[serde(with = "time::serde::iso8601")]
+    pub arrival: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub departure: OffsetDateTime,
+    /// The duration of the visit in seconds.
+    pub duration: f64,
+}
+
+/// The total time spent by a device at a place.
+#[derive(Serialize, Debug)]
+pub struct PlaceTime {
+    pub place_id: Uuid,
+    pub name: String,
+    pub visits: u64,
+    /// The total duration of the visits in seconds.
+    pub duration: f64,
+}
+
+/// Detects visits to places from reports given in ascending order of timestamp.
+///
+/// A visit begins with the first report within a place and ends with the first report outside of
+/// it, lasting until the last report within it. A report within several overlapping places is
+/// attributed to the one with the nearest center. As for stays, visits shorter than the minimum
+/// duration are ignored, as are reports with a worse accuracy than the maximum.
+pub struct VisitDetector<'a> {
+    places: &'a [Place],
+    thresholds: StayThresholds,
+    current: Option<Visit>,
+    visits: Vec<Visit>,
+}
+
+impl<'a> VisitDetector<'a> {
+    /// Creates a detector for the given places. Only the duration and accuracy thresholds apply,
+    /// as the radius of each place takes the place of the radius of a stay.
+    #[must_use]
+    pub fn new(places: &'a [Place], thresholds: StayThresholds) -> Self {
+        Self {
+            places,
+            thresholds,
+            current: None,
+            visits: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, report: &Report) {
+        if report.accuracy.to_f64().unwrap_or(f64::INFINITY) > self.thresholds.max_accuracy {
+            return;
+        }
+
+        let place = self.find_place(report);
+
+        if let Some(visit) = &mut self.current
+            && place.is_some_and(|place| place.id == visit.place_id)
+        {
+            visit.departure = report.timestamp;
+            return;
+        }
+
+        let visit = self.current.take();
+        self.end_visit(visit);
+
+        self.current = place.map(|place| Visit {
+            place_id: place.id,
+            name: place.name.clone(),
+            arrival: report.timestamp,
+            departure: report.timestamp,
+            duration: 0.0,
+        });
+    }
+
+    /// Returns the detected visits. A visit in progress at the last report is included if it has
+    /// already lasted the minimum duration.
+    #[must_use]
+    pub fn finish(mut self) -> Vec<Visit> {
+        let visit = self.current.take();
+        self.end_visit(visit);
+        self.visits
+    }
+
+    /// Returns the place containing the position of the report with the nearest center, if any.
+    fn find_place(&self, report: &Report) -> Option<&'a Place> {
+        let (latitude, longitude) = report.coordinates();
+
+        self.places
+            .iter()
+            .map(|place| {
+                let distance = geo::distance(place.latitude, place.longitude, latitude, longitude);
+
+                (place, distance)
+            })
+            .filter(|(place, distance)| *distance <= place.radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(place, _)| place)
+    }
+
+    fn end_visit(&mut self, visit: Option<Visit>) {
+        let Some(mut visit) = visit else {
+            return;
+        };
+
+        let duration = visit.departure - visit.arrival;
+
+        if duration >= self.thresholds.duration {
+            visit.duration = duration.as_seconds_f64();
+            self.visits.push(visit);
+        }
+    }
+}
+
+/// Totals the visits to each place, including places that were never visited.
+#[must_use]
+pub fn time_spent(places: &[Place], visits: &[Visit]) -> Vec<PlaceTime> {
+    places
+        .iter()
+        .map(|place| {
+            let (count, duration) = visits
+                .iter()
+                .filter(|visit| visit.place_id == place.id)
+                .fold((0, 0.0), |(count, duration), visit| {
+                    (count + 1, duration + visit.duration)
+                });
+
+            PlaceTime {
+                place_id: place.id,
+                name: place.name.clone(),
+                visits: count,
+                duration,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,391 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::ops::Sub;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use anyhow::Context;
+use bigdecimal::{BigDecimal, ToPrimitive};
+use futures::{SinkExt, StreamExt, channel::mpsc};
+use serde::Serialize;
+use sqlx::{Connection, PgConnection, PgPool};
+use time::OffsetDateTime;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::formats::{Track, TrackPoint, takeout};
+use crate::geo;
+use crate::models::{CreateReportRequest, ImportJob, ImportJobStatus, Report};
+
+/// The number of points inserted in each transaction during an import.
+const IMPORT_CHUNK_SIZE: usize = 1000;
+
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ImportSummary {
+    pub imported: u64,
+    pub duplicates: u64,
+    pub rejected: u64,
+}
+
+impl Sub for ImportSummary {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            imported: self.imported - other.imported,
+            duplicates: self.duplicates - other.duplicates,
+            rejected: self.rejected - other.rejected,
+        }
+    }
+}
+
+/// Position of the previously imported point, used to derive missing speeds and bearings.
+#[derive(Debug, Clone, Copy)]
+pub struct PreviousPoint {
+    pub timestamp: OffsetDateTime,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Imports historical points into the reports of a device.
+///
+/// Points should be provided in ascending order of timestamp, but may be split across any number of
+/// calls to [`Importer::import`]. Points with the same timestamp as an existing report of the
+/// device are skipped as duplicates.
+pub struct Importer {
+    device_id: Uuid,
+    previous: Option<PreviousPoint>,
+    summary: ImportSummary,
+}
+
+impl Importer {
+    #[must_use]
+    pub fn new(device_id: Uuid) -> Self {
+        Self::resume(device_id, None)
+    }
+
+    /// Creates an importer continuing after the given point, such as when resuming an interrupted
+    /// import job.
+    #[must_use]
+    pub fn resume(device_id: Uuid, previous: Option<PreviousPoint>) -> Self {
+        Self {
+            device_id,
+            previous,
+            summary: ImportSummary::default(),
+        }
+    }
+
+    /// Returns the last point read, which should be recorded to resume the import later.
+    #[must_use]
+    pub fn previous(&self) -> Option<PreviousPoint> {
+        self.previous
+    }
+
+    #[must_use]
+    pub fn summary(&self) -> ImportSummary {
+        self.summary
+    }
+
+    /// Counts points that could not be read from the source as rejected.
+    pub fn reject(&mut self, count: u64) {
+        self.summary.rejected += count;
+    }
+
+    #[tracing::instrument(name = "Import points", skip(self, db, points), fields(device_id = %self.device_id, count = points.len()))]
+    pub async fn import(
+        &mut self,
+        db: &mut PgConnection,
+        points: &[TrackPoint],
+    ) -> anyhow::Result<()> {
+        let (Some(since), Some(until)) = (
+            points.iter().map(|point| point.timestamp).min(),
+            points.iter().map(|point| point.timestamp).max(),
+        ) else {
+            return Ok(());
+        };
+
+        let mut timestamps: HashSet<OffsetDateTime> =
+            Report::find_timestamps(&mut *db, self.device_id, since, until)
+                .await
+                .context("Failed to fetch existing report timestamps")?
+                .into_iter()
+                .collect();
+
+        for point in points {
+            let Some(report_request) = self.report_request(point) else {
+                self.summary.rejected += 1;
+                continue;
+            };
+
+            if !timestamps.insert(point.timestamp) {
+                self.summary.duplicates += 1;
+                continue;
+            }
+
+            Report::create(&mut *db, self.device_id, &report_request, None)
+                .await
+                .context("Failed to insert imported report")?;
+
+            self.summary.imported += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Converts a point to a validated report request, deriving the speed and bearing from the
+    /// previous point if necessary.
+    fn report_request(&mut self, point: &TrackPoint) -> Option<CreateReportRequest> {
+        let latitude = point.latitude.to_f64()?;
+        let longitude = point.longitude.to_f64()?;
+
+        let (speed, bearing) = match &self.previous {
+            Some(previous) => {
+                let distance =
+                    geo::distance(previous.latitude, previous.longitude, latitude, longitude);
+                let seconds = (point.timestamp - previous.timestamp).as_seconds_f64();
+
+                (
+                    if seconds > 0.0 {
+                        distance / seconds
+                    } else {
+                        0.0
+                    },
+                    geo::bearing(previous.latitude, previous.longitude, latitude, longitude),
+                )
+            }
+            None => (0.0, 0.0),
+        };
+
+        let report_request = CreateReportRequest {
+            id: None,
+            timestamp: point.timestamp,
+            latitude: point.latitude.clone(),
+            longitude: point.longitude.clone(),
+            altitude: point.altitude.clone().unwrap_or_default(),
+            speed: match &point.speed {
+                Some(speed) => speed.clone(),
+                None => BigDecimal::try_from(speed).ok()?,
+            },
+            bearing: match &point.bearing {
+                Some(bearing) => bearing.clone(),
+                None => BigDecimal::try_from(bearing).ok()?,
+            },
+            accuracy: point.accuracy.clone().unwrap_or_default(),
+            nonce: None,
+        };
+
+        report_request.validate().ok()?;
+
+        self.previous = Some(PreviousPoint {
+            timestamp: point.timestamp,
+            latitude,
+            longitude,
+        });
+
+        Some(report_request)
+    }
+}
+
+/// Imports all points of a parsed track file.
+pub async fn import_track(
+    db: &PgPool,
+    device_id: Uuid,
+    mut track: Track,
+) -> anyhow::Result<ImportSummary> {
+    track.points.sort_by_key(|point| point.timestamp);
+
+    let mut importer = Importer::new(device_id);
+    importer.reject(track.invalid);
+
+    for chunk in track.points.chunks(IMPORT_CHUNK_SIZE) {
+        let mut transaction = db
+            .begin()
+            .await
+            .context("Failed to begin import transaction")?;
+
+        importer.import(&mut transaction, chunk).await?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit import transaction")?;
+    }
+
+    Ok(importer.summary())
+}
+
+/// Counts the bytes read from the underlying reader, allowing progress to be reported.
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.count.fetch_add(count as u64, Ordering::Relaxed);
+
+        Ok(count)
+    }
+}
+
+/// Runs an import job in the background, logging any error.
+pub fn spawn_import_job(db: PgPool, job: ImportJob) {
+    actix_web::rt::spawn(async move {
+        let id = job.id;
+
+        if let Err(e) = run_import_job(&db, job).await {
+            tracing::error!("Failed to run import job {id}: {e:?}");
+        }
+    });
+}
+
+/// Runs an import job of a Google Takeout location history export, resuming from the last
+/// recorded progress. Nothing is done if the job is already being run by another process.
+#[tracing::instrument(name = "Run import job", skip(db, job), fields(id = %job.id))]
+pub async fn run_import_job(db: &PgPool, job: ImportJob) -> anyhow::Result<ImportJob> {
+    // The lock is held for as long as the connection is open, so it is detached from the pool to
+    // ensure it is closed when the job ends.
+    let mut connection = db
+        .acquire()
+        .await
+        .context("Failed to acquire a database connection")?
+        .detach();
+
+    if !job
+        .try_lock(&mut connection)
+        .await
+        .context("Failed to lock import job")?
+    {
+        tracing::info!("Import job is already running in another process");
+        return Ok(job);
+    }
+
+    // The job may have progressed before the lock was taken.
+    let mut job = ImportJob::find_by_id(&mut connection, job.device_id, job.id)
+        .await
+        .context("Failed to fetch import job")?
+        .context("Import job no longer exists")?;
+
+    if !matches!(
+        job.status,
+        ImportJobStatus::Pending | ImportJobStatus::Running
+    ) {
+        return Ok(job);
+    }
+
+    job.set_status(&mut connection, ImportJobStatus::Running, None)
+        .await
+        .context("Failed to update import job status")?;
+
+    match process_import_job(&mut connection, &mut job).await {
+        Ok(()) => {
+            job.set_status(&mut connection, ImportJobStatus::Completed, None)
+                .await
+                .context("Failed to update import job status")?;
+
+            if job.uploaded
+                && let Err(e) = std::fs::remove_file(&job.path)
+            {
+                tracing::warn!("Failed to remove imported file {}: {e}", job.path);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Import job failed: {e:?}");
+
+            job.set_status(
+                &mut connection,
+                ImportJobStatus::Failed,
+                Some(&format!("{e:#}")),
+            )
+            .await
+            .context("Failed to update import job status")?;
+        }
+    }
+
+    Ok(job)
+}
+
+async fn process_import_job(
+    connection: &mut PgConnection,
+    job: &mut ImportJob,
+) -> anyhow::Result<()> {
+    let file = File::open(&job.path).with_context(|| format!("Failed to open {}", job.path))?;
+    let bytes_read = Arc::new(AtomicU64::new(0));
+    let reader = CountingReader {
+        inner: BufReader::new(file),
+        count: Arc::clone(&bytes_read),
+    };
+
+    let skip = u64::try_from(job.processed_records).unwrap_or_default();
+    let (mut sender, mut receiver) = mpsc::channel::<Vec<Option<TrackPoint>>>(4);
+
+    // The file is parsed on a separate thread, as reading it blocks.
+    let parser = actix_web::rt::task::spawn_blocking(move || {
+        let mut records = 0;
+        let mut chunk = Vec::with_capacity(IMPORT_CHUNK_SIZE);
+
+        takeout::read(reader, |point| {
+            // Records imported before the job was interrupted are skipped.
+            records += 1;
+
+            if records > skip {
+                chunk.push(point);
+            }
+
+            if chunk.len() >= IMPORT_CHUNK_SIZE {
+                futures::executor::block_on(sender.send(std::mem::take(&mut chunk)))?;
+            }
+
+            Ok(())
+        })?;
+
+        if !chunk.is_empty() {
+            futures::executor::block_on(sender.send(chunk))?;
+        }
+
+        anyhow::Ok(())
+    });
+
+    let mut importer = Importer::resume(job.device_id, job.previous_point());
+
+    while let Some(chunk) = receiver.next().await {
+        let records = chunk.len();
+        let mut points: Vec<TrackPoint> = chunk.into_iter().flatten().collect();
+        points.sort_by_key(|point| point.timestamp);
+
+        let before = importer.summary();
+        importer.reject((records - points.len()) as u64);
+
+        let mut transaction = connection
+            .begin()
+            .await
+            .context("Failed to begin import transaction")?;
+
+        importer.import(&mut transaction, &points).await?;
+
+        job.record_progress(
+            &mut *transaction,
+            i64::try_from(records)?,
+            i64::try_from(bytes_read.load(Ordering::Relaxed))?,
+            importer.summary() - before,
+            importer.previous(),
+        )
+        .await
+        .context("Failed to record import job progress")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit import transaction")?;
+    }
+
+    parser
+        .await
+        .context("Failed to run the location history parser")??;
+
+    Ok(())
+}
@@ -1,6 +1,13 @@
+pub mod formats;
+pub mod geo;
+pub mod geofences;
+pub mod history;
+pub mod import;
+pub mod live;
 pub mod models;
 pub mod routes;
 pub mod server;
 pub mod settings;
+pub mod signature;
 pub mod telemetry;
 pub mod util;
@@ -0,0 +1,124 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use sqlx::{PgExecutor, PgPool, postgres::PgListener};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::models::Report;
+
+/// The Postgres notification channel on which new reports are published, as the ID of the device
+/// and the ID of the report separated by a slash.
+const NOTIFICATION_CHANNEL: &str = "reports";
+
+/// The delay before reconnecting after the notification listener fails.
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Clone, Debug)]
+pub enum LiveEvent {
+    Report(Arc<Report>),
+    /// Notifications may have been missed, so any reports since the last received report should
+    /// be recovered from the database.
+    Interrupted,
+}
+
+/// Publishes a new report to every server instance sharing the database.
+///
+/// Notifications are only delivered once the surrounding transaction, if any, is committed.
+#[tracing::instrument(name = "Publish report", skip(db, report))]
+pub async fn notify<'e, E: PgExecutor<'e>>(db: E, report: &Report) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(NOTIFICATION_CHANNEL)
+        .bind(format!("{}/{}", report.device_id, report.id))
+        .execute(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Distributes reports published by any server instance to the live streams connected to this
+/// instance.
+///
+/// Subscribers receive reports from every device and are responsible for filtering them. A
+/// subscriber that falls more than the configured capacity behind misses reports, and should
+/// recover them from the database.
+#[derive(Clone)]
+pub struct Broadcaster {
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+impl Broadcaster {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self { sender }
+    }
+
+    /// Starts listening for published reports in the background, until the pool is closed.
+    pub fn spawn_listener(&self, db: PgPool) {
+        let sender = self.sender.clone();
+
+        // The listener is spawned directly onto the Tokio runtime rather than the local task set
+        // used by Actix, as dropping it requires a runtime context that is no longer available
+        // when the local task set is dropped on shutdown.
+        tokio::spawn(async move {
+            loop {
+                let Err(e) = listen(&db, &sender).await;
+
+                if matches!(e, sqlx::Error::PoolClosed) {
+                    break;
+                }
+
+                tracing::error!("Failed to listen for published reports: {e}");
+
+                actix_web::rt::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// Listens for published reports until an error occurs.
+async fn listen(
+    db: &PgPool,
+    sender: &broadcast::Sender<LiveEvent>,
+) -> Result<Infallible, sqlx::Error> {
+    let mut listener = PgListener::connect_with(db).await?;
+    listener.listen(NOTIFICATION_CHANNEL).await?;
+
+    // Sending only fails if there are no subscribers, in which case there is nothing to do.
+    sender.send(LiveEvent::Interrupted).ok();
+
+    loop {
+        // The listener reconnects automatically if the connection is lost, but any notifications
+        // sent in the meantime are lost.
+        let Some(notification) = listener.try_recv().await? else {
+            tracing::warn!("Lost connection while listening for published reports");
+            sender.send(LiveEvent::Interrupted).ok();
+            continue;
+        };
+
+        // Without subscribers there is nobody to send the report to, so it is not fetched.
+        if sender.receiver_count() == 0 {
+            continue;
+        }
+
+        let Some((Ok(device_id), Ok(id))) = notification
+            .payload()
+            .split_once('/')
+            .map(|(device_id, id)| (Uuid::parse_str(device_id), Uuid::parse_str(id)))
+        else {
+            tracing::warn!("Ignoring invalid report notification: {notification:?}");
+            continue;
+        };
+
+        if let Some(report) = Report::find_by_id(db, device_id, &id).await? {
+            sender.send(LiveEvent::Report(Arc::new(report))).ok();
+        }
+    }
+}
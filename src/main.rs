@@ -1,9 +1,118 @@
-use com_calindora_follow::server::Application;
-use com_calindora_follow::settings::get_settings;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use uuid::Uuid;
+
+use com_calindora_follow::formats;
+use com_calindora_follow::import;
+use com_calindora_follow::models::{Device, ImportJob};
+use com_calindora_follow::server::{Application, get_db_pool};
+use com_calindora_follow::settings::{Settings, get_settings};
 use com_calindora_follow::telemetry::{get_subscriber, init_subscriber};
 
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the web server (the default)
+    Serve,
+    /// Import a GPX or KML file into the history of a device
+    Import {
+        /// The API key of the device
+        #[arg(long)]
+        api_key: String,
+        /// The file to import
+        file: PathBuf,
+    },
+    /// Import a Google Takeout location history export into the history of a device, resuming
+    /// any earlier import of the same file
+    ImportTakeout {
+        /// The API key of the device
+        #[arg(long)]
+        api_key: String,
+        /// The `Records.json` or timeline export to import
+        file: PathBuf,
+    },
+}
+
+async fn run_import(settings: &Settings, api_key: &str, file: &Path) -> anyhow::Result<()> {
+    let db = get_db_pool(&settings.database)?;
+
+    let device = Device::find_by_api_key(&db, api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .context("There is no device associated with the provided API key")?;
+
+    let document = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let track = formats::parse_track(&document).context("Failed to parse track file")?;
+    let summary = import::import_track(&db, device.id, track).await?;
+
+    println!(
+        "Imported {} reports ({} duplicates, {} rejected)",
+        summary.imported, summary.duplicates, summary.rejected
+    );
+
+    Ok(())
+}
+
+async fn run_takeout_import(settings: &Settings, api_key: &str, file: &Path) -> anyhow::Result<()> {
+    let db = get_db_pool(&settings.database)?;
+
+    let device = Device::find_by_api_key(&db, api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .context("There is no device associated with the provided API key")?;
+
+    let path = file
+        .canonicalize()
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let path = path.to_string_lossy();
+
+    let unfinished = ImportJob::find_unfinished(&db, Some(device.id), Some(&path))
+        .await
+        .context("Failed to retrieve unfinished import jobs")?
+        .pop();
+
+    let job = if let Some(job) = unfinished {
+        job
+    } else {
+        let size = std::fs::metadata(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?
+            .len();
+
+        ImportJob::create(
+            &db,
+            Uuid::new_v4(),
+            device.id,
+            &path,
+            false,
+            i64::try_from(size)?,
+        )
+        .await
+        .context("Failed to create import job")?
+    };
+
+    let job = import::run_import_job(&db, job).await?;
+
+    println!(
+        "Import job {} {:?}: imported {} reports ({} duplicates, {} rejected)",
+        job.id, job.status, job.imported, job.duplicates, job.rejected
+    );
+
+    Ok(())
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    let cli = Cli::parse();
+
     init_subscriber(get_subscriber(
         "com_calindora_follow".into(),
         "info".into(),
@@ -13,11 +122,27 @@ async fn main() -> std::io::Result<()> {
     let settings = get_settings();
 
     match settings {
-        Ok(settings) => {
-            let application = Application::build(settings).await?;
+        Ok(settings) => match cli.command.unwrap_or(Command::Serve) {
+            Command::Serve => {
+                let application = Application::build(settings).await?;
 
-            application.run().await
-        }
+                application.run().await
+            }
+            Command::Import { api_key, file } => {
+                run_import(&settings, &api_key, &file).await.map_err(|e| {
+                    tracing::error!("Failed to import {}: {e:?}", file.display());
+                    std::io::Error::other("Failed to import file")
+                })
+            }
+            Command::ImportTakeout { api_key, file } => {
+                run_takeout_import(&settings, &api_key, &file)
+                    .await
+                    .map_err(|e| {
+                        tracing::error!("Failed to import {}: {e:?}", file.display());
+                        std::io::Error::other("Failed to import file")
+                    })
+            }
+        },
         Err(e) => {
             tracing::error!("Failed to read configuration: {e}");
             return Err(std::io::Error::other("Failed to read configuration"));
@@ -0,0 +1,171 @@
+use sqlx::{PgPool, types::Uuid};
+use time::{Date, OffsetDateTime};
+
+/// A local calendar day, along with the instants at which it begins and ends.
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub day: Date,
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+}
+
+/// The activity of a device over a local calendar day, cached until a report within the day is
+/// submitted or the thresholds used to compute it change.
+#[derive(Debug)]
+pub struct DailySummary {
+    pub device_id: Uuid,
+    pub timezone: String,
+    pub day: Date,
+    /// A description of the thresholds used to compute the summary.
+    pub thresholds: String,
+    pub start_timestamp: OffsetDateTime,
+    pub end_timestamp: OffsetDateTime,
+    pub reports: i64,
+    pub distance: f64,
+    pub moving_time: f64,
+    pub trips: i64,
+    pub stays: i64,
+    pub first_report: Option<OffsetDateTime>,
+    pub last_report: Option<OffsetDateTime>,
+    pub min_latitude: Option<f64>,
+    pub min_longitude: Option<f64>,
+    pub max_latitude: Option<f64>,
+    pub max_longitude: Option<f64>,
+}
+
+impl DailySummary {
+    /// Returns whether the given IANA time zone name is known to the database.
+    #[tracing::instrument(name = "Check time zone", skip(db))]
+    pub async fn timezone_exists(db: &PgPool, timezone: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1) AS "exists!""#,
+            timezone
+        )
+        .fetch_one(db)
+        .await
+    }
+
+    /// Returns each day within the given inclusive range in the given time zone.
+    #[tracing::instrument(name = "Get days in time zone", skip(db))]
+    pub async fn days(
+        db: &PgPool,
+        from: Date,
+        to: Date,
+        timezone: &str,
+    ) -> Result<Vec<Day>, sqlx::Error> {
+        let days = sqlx::query!(
+            r#"SELECT day::date AS "day!",
+                day AT TIME ZONE $3 AS "start!",
+                (day + INTERVAL '1 day') AT TIME ZONE $3 AS "end!"
+            FROM generate_series($1::date::timestamp, $2::date::timestamp, INTERVAL '1 day') AS day
+            ORDER BY day"#,
+            from,
+            to,
+            timezone
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(days
+            .into_iter()
+            .map(|day| Day {
+                day: day.day,
+                start: day.start,
+                end: day.end,
+            })
+            .collect())
+    }
+
+    /// Returns the cached summaries within the given inclusive range that were computed with the
+    /// given thresholds.
+    #[tracing::instrument(name = "Get daily summaries for device", skip(db))]
+    pub async fn find_range(
+        db: &PgPool,
+        device_id: Uuid,
+        timezone: &str,
+        thresholds: &str,
+        from: Date,
+        to: Date,
+    ) -> Result<Vec<DailySummary>, sqlx::Error> {
+        sqlx::query_as!(
+            DailySummary,
+            r#"SELECT device_id, timezone, day, thresholds, start_timestamp, end_timestamp, reports,
+                distance, moving_time, trips, stays, first_report, last_report, min_latitude,
+                min_longitude, max_latitude, max_longitude
+            FROM daily_summaries
+            WHERE device_id = $1 AND timezone = $2 AND thresholds = $3 AND day >= $4 AND day <= $5
+            ORDER BY day"#,
+            device_id,
+            timezone,
+            thresholds,
+            from,
+            to
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Caches the summary, replacing any computed with other thresholds, unless a report within
+    /// the day has been submitted for the device since the given sequence number was read, as the
+    /// summary may not include it.
+    ///
+    /// The device is locked before checking, so that a report being inserted concurrently either
+    /// has been committed and is seen here, or invalidates the summary after it is cached.
+    #[tracing::instrument(name = "Insert daily summary", skip(db, self))]
+    pub async fn create_if_unchanged(&self, db: &PgPool, sequence: i64) -> Result<(), sqlx::Error> {
+        let mut transaction = db.begin().await?;
+
+        let device = sqlx::query_scalar!(
+            "SELECT id FROM devices WHERE id = $1 FOR SHARE",
+            self.device_id
+        )
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        if device.is_none() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"INSERT INTO daily_summaries (device_id, timezone, day, start_timestamp, end_timestamp,
+                reports, distance, moving_time, trips, first_report, last_report, min_latitude,
+                min_longitude, max_latitude, max_longitude, stays, thresholds)
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $18
+            WHERE NOT EXISTS (
+                SELECT 1 FROM reports
+                WHERE device_id = $1 AND sequence > $17 AND timestamp >= $4 AND timestamp < $5
+            )
+            ON CONFLICT (device_id, timezone, day) DO UPDATE SET
+                start_timestamp = EXCLUDED.start_timestamp, end_timestamp = EXCLUDED.end_timestamp,
+                reports = EXCLUDED.reports, distance = EXCLUDED.distance,
+                moving_time = EXCLUDED.moving_time, trips = EXCLUDED.trips,
+                first_report = EXCLUDED.first_report, last_report = EXCLUDED.last_report,
+                min_latitude = EXCLUDED.min_latitude, min_longitude = EXCLUDED.min_longitude,
+                max_latitude = EXCLUDED.max_latitude, max_longitude = EXCLUDED.max_longitude,
+                stays = EXCLUDED.stays, thresholds = EXCLUDED.thresholds
+            WHERE daily_summaries.thresholds <> EXCLUDED.thresholds"#,
+            self.device_id,
+            self.timezone,
+            self.day,
+            self.start_timestamp,
+            self.end_timestamp,
+            self.reports,
+            self.distance,
+            self.moving_time,
+            self.trips,
+            self.first_report,
+            self.last_report,
+            self.min_latitude,
+            self.min_longitude,
+            self.max_latitude,
+            self.max_longitude,
+            self.stays,
+            sequence,
+            self.thresholds
+        )
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await
+    }
+}
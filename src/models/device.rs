@@ -1,5 +1,6 @@
 use secrecy::SecretString;
-use sqlx::{PgPool, types::Uuid};
+use sqlx::{PgConnection, PgPool, types::Uuid};
+use time::{Duration, OffsetDateTime};
 
 #[derive(serde::Serialize)]
 pub struct Device {
@@ -7,6 +8,7 @@ pub struct Device {
     pub api_key: String,
     #[serde(skip_serializing)]
     pub api_secret: SecretString,
+    pub osmand_enabled: bool,
 }
 
 impl Device {
@@ -24,8 +26,76 @@ pub async fn find_by_api_key(
                 id: device.id,
                 api_key: device.api_key,
                 api_secret: device.api_secret.into(),
+                osmand_enabled: device.osmand_enabled,
             })),
             _ => Ok(None),
         }
     }
+
+    /// Returns the devices whose locations are shared with this device.
+    #[tracing::instrument(name = "Get friends of device", skip(self, db))]
+    pub async fn find_friends(&self, db: &PgPool) -> Result<Vec<Device>, sqlx::Error> {
+        let devices = sqlx::query!(
+            r#"SELECT devices.* FROM devices
+            JOIN device_friends ON device_friends.friend_id = devices.id
+            WHERE device_friends.device_id = $1
+            ORDER BY devices.api_key"#,
+            self.id
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(devices
+            .into_iter()
+            .map(|device| Device {
+                id: device.id,
+                api_key: device.api_key,
+                api_secret: device.api_secret.into(),
+                osmand_enabled: device.osmand_enabled,
+            })
+            .collect())
+    }
+
+    /// Returns the sequence number of the most recent report submitted for this device.
+    #[tracing::instrument(name = "Get report sequence of device", skip(self, db))]
+    pub async fn last_report_sequence(&self, db: &PgPool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT last_report_sequence FROM devices WHERE id = $1",
+            self.id
+        )
+        .fetch_one(db)
+        .await
+    }
+
+    /// Records a nonce as used by this device, returning `false` if it has already been used.
+    ///
+    /// Nonces are retained until the timestamp of the report they were submitted with falls
+    /// outside the retention window, at which point the report itself would be rejected.
+    #[tracing::instrument(name = "Claim nonce for device", skip(self, db, nonce))]
+    pub async fn claim_nonce(
+        &self,
+        db: &mut PgConnection,
+        nonce: &str,
+        timestamp: OffsetDateTime,
+        retention: Duration,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM report_nonces WHERE device_id = $1 AND timestamp < $2",
+            self.id,
+            OffsetDateTime::now_utc() - retention
+        )
+        .execute(&mut *db)
+        .await?;
+
+        let result = sqlx::query!(
+            "INSERT INTO report_nonces (device_id, nonce, timestamp) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            self.id,
+            nonce,
+            timestamp
+        )
+        .execute(&mut *db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
 }
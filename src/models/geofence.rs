@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize, Serializer};
+use sqlx::{PgConnection, PgPool, types::Uuid};
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::models::Report;
+
+/// A circular or polygonal area whose boundary crossings by a device are recorded as events.
+#[derive(Serialize, Debug, Clone)]
+pub struct Geofence {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub device_id: Uuid,
+    pub name: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// The radius of a circular geofence in meters.
+    pub radius: Option<f64>,
+    /// The `GeoJSON` geometry of a polygonal geofence.
+    #[serde(serialize_with = "serialize_geometry")]
+    pub geometry: Option<String>,
+    /// The distance in meters beyond the boundary a device must travel to exit the geofence.
+    pub hysteresis: f64,
+    /// Whether the device was within the geofence as of the state timestamp.
+    pub inside: bool,
+    /// The timestamp of the most recent report evaluated against the geofence.
+    #[serde(skip_serializing)]
+    pub state_timestamp: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+// The signature is dictated by `serialize_with`.
+#[expect(clippy::ref_option)]
+fn serialize_geometry<S: Serializer>(
+    geometry: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    geometry
+        .as_deref()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .transpose()
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct CreateGeofenceRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub latitude: Option<f64>,
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub longitude: Option<f64>,
+    #[validate(range(exclusive_min = 0.0, max = 100_000.0))]
+    pub radius: Option<f64>,
+    pub geometry: Option<serde_json::Value>,
+    #[validate(range(min = 0.0, max = 10_000.0))]
+    pub hysteresis: Option<f64>,
+}
+
+impl Geofence {
+    /// Inserts a geofence, returning `None` if the device already has a geofence with the same
+    /// name. The geometry should already have been validated.
+    #[tracing::instrument(name = "Insert geofence", skip(db))]
+    pub async fn create(
+        db: &PgPool,
+        device_id: Uuid,
+        request: &CreateGeofenceRequest,
+        hysteresis: f64,
+    ) -> Result<Option<Geofence>, sqlx::Error> {
+        sqlx::query_as!(
+            Geofence,
+            r#"INSERT INTO geofences (id, device_id, name, latitude, longitude, radius, geometry,
+                hysteresis, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (device_id, name) DO NOTHING
+            RETURNING *"#,
+            Uuid::new_v4(),
+            device_id,
+            request.name,
+            request.latitude,
+            request.longitude,
+            request.radius,
+            request.geometry.as_ref().map(serde_json::Value::to_string),
+            hysteresis,
+            OffsetDateTime::now_utc()
+        )
+        .fetch_optional(db)
+        .await
+    }
+
+    #[tracing::instrument(name = "Get geofences for device", skip(db))]
+    pub async fn find_by_device(
+        db: &PgPool,
+        device_id: Uuid,
+    ) -> Result<Vec<Geofence>, sqlx::Error> {
+        sqlx::query_as!(
+            Geofence,
+            "SELECT * FROM geofences WHERE device_id = $1 ORDER BY name",
+            device_id
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Returns the geofences of a device, locking them until the end of the transaction so that
+    /// concurrent reports are evaluated one at a time.
+    #[tracing::instrument(name = "Lock geofences for device", skip(db))]
+    pub async fn lock_by_device(
+        db: &mut PgConnection,
+        device_id: Uuid,
+    ) -> Result<Vec<Geofence>, sqlx::Error> {
+        sqlx::query_as!(
+            Geofence,
+            "SELECT * FROM geofences WHERE device_id = $1 ORDER BY id FOR UPDATE",
+            device_id
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    #[tracing::instrument(name = "Update geofence state", skip(self, db))]
+    pub async fn update_state(
+        &self,
+        db: &mut PgConnection,
+        inside: bool,
+        timestamp: OffsetDateTime,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE geofences SET inside = $2, state_timestamp = $3 WHERE id = $1",
+            self.id,
+            inside,
+            timestamp
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes a geofence along with its events, returning whether it existed.
+    #[tracing::instrument(name = "Delete geofence", skip(db))]
+    pub async fn delete(db: &PgPool, device_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM geofences WHERE device_id = $1 AND id = $2",
+            device_id,
+            id
+        )
+        .execute(db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum GeofenceEventKind {
+    Enter,
+    Exit,
+}
+
+/// The crossing of the boundary of a geofence by a device.
+#[derive(Serialize, Debug)]
+pub struct GeofenceEvent {
+    pub id: Uuid,
+    pub geofence_id: Uuid,
+    pub name: String,
+    pub report_id: Uuid,
+    pub kind: GeofenceEventKind,
+    #[serde(with = "time::serde::iso8601")]
+    pub timestamp: OffsetDateTime,
+}
+
+impl GeofenceEvent {
+    #[tracing::instrument(name = "Insert geofence event", skip(db, geofence, report))]
+    pub async fn create(
+        db: &mut PgConnection,
+        geofence: &Geofence,
+        report: &Report,
+        kind: GeofenceEventKind,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO geofence_events (id, geofence_id, device_id, report_id, kind, timestamp)
+            VALUES ($1, $2, $3, $4, $5, $6)"#,
+            Uuid::new_v4(),
+            geofence.id,
+            geofence.device_id,
+            report.id,
+            kind as GeofenceEventKind,
+            report.timestamp
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the events of a device within the exclusive range, in ascending order of
+    /// timestamp.
+    #[tracing::instrument(name = "Get geofence events for device", skip(db))]
+    pub async fn find_range(
+        db: &PgPool,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+    ) -> Result<Vec<GeofenceEvent>, sqlx::Error> {
+        sqlx::query_as!(
+            GeofenceEvent,
+            r#"SELECT geofence_events.id, geofence_id, geofences.name, report_id,
+                kind AS "kind: GeofenceEventKind", timestamp
+            FROM geofence_events
+            JOIN geofences ON geofences.id = geofence_events.geofence_id
+            WHERE geofence_events.device_id = $1 AND timestamp > $2 AND timestamp < $3
+            ORDER BY timestamp, geofence_events.id"#,
+            device_id,
+            since,
+            until
+        )
+        .fetch_all(db)
+        .await
+    }
+}
@@ -0,0 +1,222 @@
+use serde::Serialize;
+use sqlx::{PgConnection, PgExecutor, PgPool, types::Uuid};
+use time::OffsetDateTime;
+
+use crate::import::{ImportSummary, PreviousPoint};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum ImportJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A background import of a file stored on the server.
+#[derive(Serialize, Debug)]
+pub struct ImportJob {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub device_id: Uuid,
+    #[serde(skip_serializing)]
+    pub path: String,
+    /// Whether the file was uploaded to the server, in which case it is deleted once the job
+    /// completes.
+    #[serde(skip_serializing)]
+    pub uploaded: bool,
+    pub status: ImportJobStatus,
+    pub total_bytes: i64,
+    pub processed_bytes: i64,
+    pub processed_records: i64,
+    pub imported: i64,
+    pub duplicates: i64,
+    pub rejected: i64,
+    pub error: Option<String>,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    pub updated_at: OffsetDateTime,
+    /// The last point read before the recorded progress, from which the speed and bearing of the
+    /// next point are derived when the job is resumed.
+    #[serde(skip_serializing)]
+    pub previous_timestamp: Option<OffsetDateTime>,
+    #[serde(skip_serializing)]
+    pub previous_latitude: Option<f64>,
+    #[serde(skip_serializing)]
+    pub previous_longitude: Option<f64>,
+}
+
+impl ImportJob {
+    #[must_use]
+    pub fn previous_point(&self) -> Option<PreviousPoint> {
+        match (
+            self.previous_timestamp,
+            self.previous_latitude,
+            self.previous_longitude,
+        ) {
+            (Some(timestamp), Some(latitude), Some(longitude)) => Some(PreviousPoint {
+                timestamp,
+                latitude,
+                longitude,
+            }),
+            _ => None,
+        }
+    }
+
+    #[tracing::instrument(name = "Insert import job", skip(db))]
+    pub async fn create(
+        db: &PgPool,
+        id: Uuid,
+        device_id: Uuid,
+        path: &str,
+        uploaded: bool,
+        total_bytes: i64,
+    ) -> Result<ImportJob, sqlx::Error> {
+        let now = OffsetDateTime::now_utc();
+
+        sqlx::query_as!(
+            ImportJob,
+            r#"INSERT INTO import_jobs (id, device_id, path, uploaded, total_bytes, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            RETURNING id, device_id, path, uploaded, status AS "status: ImportJobStatus", total_bytes,
+                processed_bytes, processed_records, imported, duplicates, rejected, error,
+                created_at, updated_at, previous_timestamp, previous_latitude, previous_longitude"#,
+            id,
+            device_id,
+            path,
+            uploaded,
+            total_bytes,
+            now
+        )
+        .fetch_one(db)
+        .await
+    }
+
+    #[tracing::instrument(name = "Get import job", skip(db))]
+    pub async fn find_by_id<'e, E: PgExecutor<'e>>(
+        db: E,
+        device_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<ImportJob>, sqlx::Error> {
+        sqlx::query_as!(
+            ImportJob,
+            r#"SELECT id, device_id, path, uploaded, status AS "status: ImportJobStatus", total_bytes,
+                processed_bytes, processed_records, imported, duplicates, rejected, error,
+                created_at, updated_at, previous_timestamp, previous_latitude, previous_longitude
+            FROM import_jobs WHERE device_id = $1 AND id = $2"#,
+            device_id,
+            id
+        )
+        .fetch_optional(db)
+        .await
+    }
+
+    /// Returns the jobs that have not yet completed or failed, including any that were running
+    /// when the server last stopped.
+    #[tracing::instrument(name = "Get unfinished import jobs", skip(db))]
+    pub async fn find_unfinished(
+        db: &PgPool,
+        device_id: Option<Uuid>,
+        path: Option<&str>,
+    ) -> Result<Vec<ImportJob>, sqlx::Error> {
+        sqlx::query_as!(
+            ImportJob,
+            r#"SELECT id, device_id, path, uploaded, status AS "status: ImportJobStatus", total_bytes,
+                processed_bytes, processed_records, imported, duplicates, rejected, error,
+                created_at, updated_at, previous_timestamp, previous_latitude, previous_longitude
+            FROM import_jobs
+            WHERE status IN ('pending', 'running')
+                AND ($1::uuid IS NULL OR device_id = $1)
+                AND ($2::varchar IS NULL OR path = $2)
+            ORDER BY created_at"#,
+            device_id,
+            path
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Attempts to take the session-level advisory lock for this job, preventing it from being
+    /// run by more than one process at a time.
+    #[tracing::instrument(name = "Lock import job", skip(self, db), fields(id = %self.id))]
+    pub async fn try_lock(&self, db: &mut PgConnection) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock($1) AS "locked!""#,
+            self.id.as_u64_pair().0.cast_signed()
+        )
+        .fetch_one(db)
+        .await
+    }
+
+    #[tracing::instrument(name = "Update import job status", skip(self, db), fields(id = %self.id))]
+    pub async fn set_status<'e, E: PgExecutor<'e>>(
+        &mut self,
+        db: E,
+        status: ImportJobStatus,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        self.updated_at = OffsetDateTime::now_utc();
+
+        sqlx::query!(
+            "UPDATE import_jobs SET status = $2, error = $3, updated_at = $4 WHERE id = $1",
+            self.id,
+            status as ImportJobStatus,
+            error,
+            self.updated_at
+        )
+        .execute(db)
+        .await?;
+
+        self.status = status;
+        self.error = error.map(ToString::to_string);
+
+        Ok(())
+    }
+
+    /// Records the progress made by a chunk of the import. This should be called in the same
+    /// transaction as the chunk is imported, so that resuming the job skips exactly the records
+    /// that have already been imported.
+    #[tracing::instrument(name = "Update import job progress", skip(self, db), fields(id = %self.id))]
+    pub async fn record_progress<'e, E: PgExecutor<'e>>(
+        &mut self,
+        db: E,
+        records: i64,
+        processed_bytes: i64,
+        summary: ImportSummary,
+        previous: Option<PreviousPoint>,
+    ) -> Result<(), sqlx::Error> {
+        self.processed_records += records;
+        self.processed_bytes = processed_bytes;
+        self.imported += i64::try_from(summary.imported).unwrap_or(i64::MAX);
+        self.duplicates += i64::try_from(summary.duplicates).unwrap_or(i64::MAX);
+        self.rejected += i64::try_from(summary.rejected).unwrap_or(i64::MAX);
+        self.updated_at = OffsetDateTime::now_utc();
+        self.previous_timestamp = previous.map(|previous| previous.timestamp);
+        self.previous_latitude = previous.map(|previous| previous.latitude);
+        self.previous_longitude = previous.map(|previous| previous.longitude);
+
+        sqlx::query!(
+            r#"UPDATE import_jobs
+            SET processed_records = $2, processed_bytes = $3, imported = $4, duplicates = $5,
+                rejected = $6, updated_at = $7, previous_timestamp = $8, previous_latitude = $9,
+                previous_longitude = $10
+            WHERE id = $1"#,
+            self.id,
+            self.processed_records,
+            self.processed_bytes,
+            self.imported,
+            self.duplicates,
+            self.rejected,
+            self.updated_at,
+            self.previous_timestamp,
+            self.previous_latitude,
+            self.previous_longitude
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,13 @@
+pub mod daily_summary;
 pub mod device;
+pub mod geofence;
+pub mod import_job;
+pub mod place;
 pub mod report;
 
+pub use daily_summary::*;
 pub use device::*;
+pub use geofence::*;
+pub use import_job::*;
+pub use place::*;
 pub use report::*;
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, types::Uuid};
+use time::OffsetDateTime;
+use validator::Validate;
+
+/// A named circular area, such as a home or workplace, at which visits of a device are recorded.
+#[derive(Serialize, Debug, Clone)]
+pub struct Place {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub device_id: Uuid,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// The radius of the place in meters.
+    pub radius: f64,
+    #[serde(with = "time::serde::iso8601")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct CreatePlaceRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub latitude: f64,
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub longitude: f64,
+    #[validate(range(exclusive_min = 0.0, max = 100_000.0))]
+    pub radius: f64,
+}
+
+impl Place {
+    /// Inserts a place, returning `None` if the device already has a place with the same name.
+    #[tracing::instrument(name = "Insert place", skip(db))]
+    pub async fn create(
+        db: &PgPool,
+        device_id: Uuid,
+        request: &CreatePlaceRequest,
+    ) -> Result<Option<Place>, sqlx::Error> {
+        sqlx::query_as!(
+            Place,
+            r#"INSERT INTO places (id, device_id, name, latitude, longitude, radius, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (device_id, name) DO NOTHING
+            RETURNING *"#,
+            Uuid::new_v4(),
+            device_id,
+            request.name,
+            request.latitude,
+            request.longitude,
+            request.radius,
+            OffsetDateTime::now_utc()
+        )
+        .fetch_optional(db)
+        .await
+    }
+
+    #[tracing::instrument(name = "Get places for device", skip(db))]
+    pub async fn find_by_device(db: &PgPool, device_id: Uuid) -> Result<Vec<Place>, sqlx::Error> {
+        sqlx::query_as!(
+            Place,
+            "SELECT * FROM places WHERE device_id = $1 ORDER BY name",
+            device_id
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Deletes a place, returning whether it existed.
+    #[tracing::instrument(name = "Delete place", skip(db))]
+    pub async fn delete(db: &PgPool, device_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM places WHERE device_id = $1 AND id = $2",
+            device_id,
+            id
+        )
+        .execute(db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,17 +1,19 @@
 use std::str::FromStr;
 
 use anyhow::Context;
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive};
+use futures::stream::BoxStream;
 use hmac::{Hmac, KeyInit, Mac};
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
-use sqlx::{PgPool, types::Uuid};
-use time::OffsetDateTime;
+use sqlx::{PgExecutor, PgPool, types::Uuid};
+use time::{Duration, OffsetDateTime};
 use validator::{Validate, ValidationError};
 
-use crate::util::TIMESTAMP_FORMAT;
+use crate::geo::{self, Area, Ring};
+use crate::util::{TIMESTAMP_FORMAT, TIMESTAMP_FORMAT_SUBSECOND};
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Debug)]
 pub struct Report {
     pub id: Uuid,
     #[serde(skip_serializing)]
@@ -26,19 +28,354 @@ pub struct Report {
     pub speed: BigDecimal,
     pub bearing: BigDecimal,
     pub accuracy: BigDecimal,
+    #[serde(skip_serializing)]
+    pub idempotency_key: Option<String>,
+    pub sequence: i64,
 }
 
 impl Report {
-    #[tracing::instrument(name = "Get device from ID", skip(db, id))]
-    pub async fn find_by_id(db: &PgPool, id: &Uuid) -> Result<Option<Report>, sqlx::Error> {
-        sqlx::query_as!(Report, "SELECT * FROM reports WHERE id = $1", &id)
-            .fetch_optional(db)
-            .await
+    /// Returns the latitude and longitude of the report in degrees.
+    #[must_use]
+    pub fn coordinates(&self) -> (f64, f64) {
+        (
+            self.latitude.to_f64().unwrap_or_default(),
+            self.longitude.to_f64().unwrap_or_default(),
+        )
+    }
+
+    #[tracing::instrument(name = "Get report from ID", skip(db, id))]
+    pub async fn find_by_id(
+        db: &PgPool,
+        device_id: Uuid,
+        id: &Uuid,
+    ) -> Result<Option<Report>, sqlx::Error> {
+        sqlx::query_as!(
+            Report,
+            "SELECT * FROM reports WHERE device_id = $1 AND id = $2",
+            device_id,
+            id
+        )
+        .fetch_optional(db)
+        .await
+    }
+
+    /// Returns the reports with the given IDs in ascending order of timestamp and ID.
+    #[tracing::instrument(name = "Get reports from IDs", skip(db, ids))]
+    pub async fn find_by_ids(
+        db: &PgPool,
+        device_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Report>, sqlx::Error> {
+        sqlx::query_as!(
+            Report,
+            r#"SELECT * FROM reports
+            WHERE device_id = $1 AND id = ANY($2)
+            ORDER BY timestamp ASC, id ASC"#,
+            device_id,
+            ids
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Returns reports in ascending order of timestamp and ID, starting after the given position.
+    #[tracing::instrument(name = "Get page of reports for device", skip(db))]
+    pub async fn find_page(
+        db: &PgPool,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+        area: Option<&Area>,
+        after: Option<(OffsetDateTime, Uuid)>,
+        limit: i64,
+    ) -> Result<Vec<Report>, sqlx::Error> {
+        let (after_timestamp, after_id) = after.unzip();
+        let (exteriors, holes, hole_polygons) = area_parameters(area);
+
+        sqlx::query_as!(
+            Report,
+            r#"SELECT * FROM reports
+            WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3
+                AND ($4::timestamptz IS NULL OR (timestamp, id) > ($4, $5::uuid))
+                AND area_contains($6, $7, $8, latitude, longitude)
+            ORDER BY timestamp ASC, id ASC
+            LIMIT $9"#,
+            device_id,
+            since,
+            until,
+            after_timestamp,
+            after_id,
+            exteriors.as_deref(),
+            holes.as_deref(),
+            hole_polygons.as_deref(),
+            limit
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Returns reports in descending order of timestamp and ID, starting before the given position.
+    #[tracing::instrument(name = "Get descending page of reports for device", skip(db))]
+    pub async fn find_page_descending(
+        db: &PgPool,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+        area: Option<&Area>,
+        before: Option<(OffsetDateTime, Uuid)>,
+        limit: i64,
+    ) -> Result<Vec<Report>, sqlx::Error> {
+        let (before_timestamp, before_id) = before.unzip();
+        let (exteriors, holes, hole_polygons) = area_parameters(area);
+
+        sqlx::query_as!(
+            Report,
+            r#"SELECT * FROM reports
+            WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3
+                AND ($4::timestamptz IS NULL OR (timestamp, id) < ($4, $5::uuid))
+                AND area_contains($6, $7, $8, latitude, longitude)
+            ORDER BY timestamp DESC, id DESC
+            LIMIT $9"#,
+            device_id,
+            since,
+            until,
+            before_timestamp,
+            before_id,
+            exteriors.as_deref(),
+            holes.as_deref(),
+            hole_polygons.as_deref(),
+            limit
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Streams all reports within the given exclusive range in ascending order of timestamp and ID.
+    #[must_use]
+    pub fn stream<'a>(
+        db: &'a PgPool,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+        area: Option<&Area>,
+    ) -> BoxStream<'a, Result<Report, sqlx::Error>> {
+        let (exteriors, holes, hole_polygons) = area_parameters(area);
+
+        sqlx::query_as!(
+            Report,
+            r#"SELECT * FROM reports
+            WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3
+                AND area_contains($4, $5, $6, latitude, longitude)
+            ORDER BY timestamp ASC, id ASC"#,
+            device_id,
+            since,
+            until,
+            exteriors.as_deref(),
+            holes.as_deref(),
+            hole_polygons.as_deref()
+        )
+        .fetch(db)
+    }
+
+    /// Returns a stream of the reports within the given exclusive range that lie within a box
+    /// around every point within the radius of the center, along with each report immediately
+    /// following one within the box, so that leaving the radius is still observed.
+    #[must_use]
+    pub fn stream_near(
+        db: &PgPool,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+        (latitude, longitude): (f64, f64),
+        radius: f64,
+    ) -> BoxStream<'_, Result<Report, sqlx::Error>> {
+        let (latitude_extent, longitude_extent) = geo::radius_extent(latitude, radius);
+
+        sqlx::query_as!(
+            Report,
+            r#"WITH candidates AS (
+                SELECT *,
+                    ABS(latitude - $4::FLOAT8) <= $6::FLOAT8
+                        AND ABS(MOD(longitude + (540 - $5::FLOAT8)::NUMERIC, 360) - 180)
+                            <= $7::FLOAT8 AS inside
+                FROM reports
+                WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3
+            ), flagged AS (
+                SELECT *, LAG(inside, 1, FALSE) OVER (ORDER BY timestamp ASC, id ASC) AS follows
+                FROM candidates
+            )
+            SELECT id AS "id!", device_id AS "device_id!", timestamp AS "timestamp!",
+                submit_timestamp, latitude AS "latitude!", longitude AS "longitude!",
+                altitude AS "altitude!", speed AS "speed!", bearing AS "bearing!",
+                accuracy AS "accuracy!", idempotency_key, sequence AS "sequence!"
+            FROM flagged
+            WHERE inside OR follows
+            ORDER BY timestamp ASC, id ASC"#,
+            device_id,
+            since,
+            until,
+            latitude,
+            longitude,
+            latitude_extent,
+            longitude_extent
+        )
+        .fetch(db)
+    }
+
+    /// Returns the number of reports within the given exclusive range.
+    #[tracing::instrument(name = "Count reports for device", skip(db))]
+    pub async fn count(
+        db: &PgPool,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+        area: Option<&Area>,
+    ) -> Result<i64, sqlx::Error> {
+        let (exteriors, holes, hole_polygons) = area_parameters(area);
+
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM reports
+            WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3
+                AND area_contains($4, $5, $6, latitude, longitude)
+            "#,
+            device_id,
+            since,
+            until,
+            exteriors.as_deref(),
+            holes.as_deref(),
+            hole_polygons.as_deref()
+        )
+        .fetch_one(db)
+        .await
+    }
+
+    #[tracing::instrument(name = "Get latest report for device", skip(db))]
+    pub async fn find_latest(db: &PgPool, device_id: Uuid) -> Result<Option<Report>, sqlx::Error> {
+        sqlx::query_as!(
+            Report,
+            "SELECT * FROM reports WHERE device_id = $1 ORDER BY timestamp DESC, id DESC LIMIT 1",
+            device_id
+        )
+        .fetch_optional(db)
+        .await
+    }
+
+    /// Returns reports in order of sequence number, starting after the given sequence number.
+    #[tracing::instrument(name = "Get changed reports for device", skip(db))]
+    pub async fn find_changed(
+        db: &PgPool,
+        device_id: Uuid,
+        after_sequence: i64,
+        area: Option<&Area>,
+        limit: i64,
+    ) -> Result<Vec<Report>, sqlx::Error> {
+        let (exteriors, holes, hole_polygons) = area_parameters(area);
+
+        sqlx::query_as!(
+            Report,
+            r#"SELECT * FROM reports
+            WHERE device_id = $1 AND sequence > $2
+                AND area_contains($3, $4, $5, latitude, longitude)
+            ORDER BY sequence ASC
+            LIMIT $6"#,
+            device_id,
+            after_sequence,
+            exteriors.as_deref(),
+            holes.as_deref(),
+            hole_polygons.as_deref(),
+            limit
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Returns the timestamps of all reports within the given inclusive range.
+    #[tracing::instrument(name = "Get report timestamps for device", skip(db))]
+    pub async fn find_timestamps<'e, E: PgExecutor<'e>>(
+        db: E,
+        device_id: Uuid,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+    ) -> Result<Vec<OffsetDateTime>, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT timestamp FROM reports WHERE device_id = $1 AND timestamp >= $2 AND timestamp <= $3",
+            device_id,
+            since,
+            until
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    /// Inserts a new report, returning `None` if the report ID or idempotency key is already in use.
+    ///
+    /// Each report is assigned the next sequence number of its device. The device is locked until
+    /// the surrounding transaction ends, so reports become visible in sequence order.
+    #[tracing::instrument(name = "Insert report", skip(db, request, idempotency_key))]
+    pub async fn create<'e, E: PgExecutor<'e>>(
+        db: E,
+        device_id: Uuid,
+        request: &CreateReportRequest,
+        idempotency_key: Option<&str>,
+    ) -> Result<Option<Report>, sqlx::Error> {
+        sqlx::query_as!(Report,
+                r#"WITH sequence AS (
+                    UPDATE devices SET last_report_sequence = last_report_sequence + 1
+                    WHERE id = $2
+                    RETURNING last_report_sequence
+                ), invalidated AS (
+                    DELETE FROM daily_summaries
+                    WHERE device_id = $2 AND start_timestamp <= $3 AND end_timestamp > $3
+                ), inserted AS (
+                    INSERT INTO reports (id, device_id, timestamp, submit_timestamp, latitude, longitude, altitude, speed, bearing, accuracy, idempotency_key, sequence)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, (SELECT last_report_sequence FROM sequence))
+                    ON CONFLICT DO NOTHING
+                    RETURNING *
+                )
+                SELECT * FROM inserted"#,
+            request.id.unwrap_or_else(Uuid::new_v4),
+            device_id,
+            request.timestamp,
+            OffsetDateTime::now_utc(),
+            request.latitude,
+            request.longitude,
+            request.altitude,
+            request.speed,
+            request.bearing,
+            request.accuracy,
+            idempotency_key
+        )
+        .fetch_optional(db)
+        .await
+    }
+
+    /// Finds the report of the device that prevented a report with the given ID or idempotency key
+    /// from being inserted, preferring the report with the same ID.
+    #[tracing::instrument(name = "Get conflicting report", skip(db, id, idempotency_key))]
+    pub async fn find_conflicting<'e, E: PgExecutor<'e>>(
+        db: E,
+        device_id: Uuid,
+        id: Option<Uuid>,
+        idempotency_key: Option<&str>,
+    ) -> Result<Option<Report>, sqlx::Error> {
+        sqlx::query_as!(
+            Report,
+            r#"SELECT * FROM reports
+            WHERE device_id = $1 AND (id = $2 OR idempotency_key = $3)
+            ORDER BY id = $2 DESC NULLS LAST
+            LIMIT 1"#,
+            device_id,
+            id,
+            idempotency_key
+        )
+        .fetch_optional(db)
+        .await
     }
 }
 
 #[derive(Deserialize, Debug, Validate)]
 pub struct CreateReportRequest {
+    pub id: Option<Uuid>,
     #[serde(with = "time::serde::iso8601")]
     pub timestamp: OffsetDateTime,
     #[validate(custom(function = "validate_latitude"))]
@@ -52,27 +389,70 @@ pub struct CreateReportRequest {
     pub bearing: BigDecimal,
     #[validate(custom(function = "validate_positive"))]
     pub accuracy: BigDecimal,
+    #[validate(length(min = 8, max = 128))]
+    pub nonce: Option<String>,
 }
 
 impl CreateReportRequest {
+    /// Returns whether the request describes the given report, as stored with the precision of
+    /// the database.
+    #[must_use]
+    pub fn matches(&self, report: &Report) -> bool {
+        let round = |value: &BigDecimal| value.with_scale_round(12, RoundingMode::HalfUp);
+
+        (self.timestamp - report.timestamp).abs() < Duration::microseconds(1)
+            && round(&self.latitude) == report.latitude
+            && round(&self.longitude) == report.longitude
+            && round(&self.altitude) == report.altitude
+            && round(&self.speed) == report.speed
+            && round(&self.bearing) == report.bearing
+            && round(&self.accuracy) == report.accuracy
+    }
+
     pub fn get_signature(&self, secret: &str) -> anyhow::Result<String> {
-        type HmacSha256 = Hmac<Sha256>;
+        Ok(hex::encode(self.mac(secret)?.finalize().into_bytes()))
+    }
+
+    /// Checks the version 1 signature of the request in constant time.
+    pub fn verify_signature(&self, secret: &str, signature: &str) -> anyhow::Result<bool> {
+        let Ok(signature) = hex::decode(signature) else {
+            return Ok(false);
+        };
+
+        Ok(self.mac(secret)?.verify_slice(&signature).is_ok())
+    }
+
+    /// Returns the HMAC of the signed fields of the request, from which its signature is derived.
+    fn mac(&self, secret: &str) -> anyhow::Result<Hmac<Sha256>> {
+        // Requests with a nonce also sign the subsecond portion of the timestamp, which older
+        // clients without a nonce never included.
+        let timestamp_format = if self.nonce.is_some() {
+            TIMESTAMP_FORMAT_SUBSECOND
+        } else {
+            TIMESTAMP_FORMAT
+        };
 
         let timestamp = &self
             .timestamp
-            .format(TIMESTAMP_FORMAT)
+            .format(timestamp_format)
             .context("Failed to format timestamp for signature generation")?;
 
         let input = format!(
-            "{timestamp}{:.12}{:.12}{:.12}{:.12}{:.12}{:.12}",
-            self.latitude, self.longitude, self.altitude, self.speed, self.bearing, self.accuracy
+            "{timestamp}{:.12}{:.12}{:.12}{:.12}{:.12}{:.12}{}",
+            self.latitude,
+            self.longitude,
+            self.altitude,
+            self.speed,
+            self.bearing,
+            self.accuracy,
+            self.nonce.as_deref().unwrap_or_default()
         );
 
-        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
             .context("Failed to create HMAC instance for signature generation")?;
         mac.update(input.as_bytes());
 
-        Ok(hex::encode(mac.finalize().into_bytes()))
+        Ok(mac)
     }
 }
 
@@ -119,3 +499,39 @@ fn validate_bearing(value: &BigDecimal) -> Result<(), ValidationError> {
         Ok(())
     }
 }
+
+/// Converts an optional area to the parameters of the `area_contains` SQL function: the exterior
+/// rings and holes as Postgres polygon literals, and the one-based index of the exterior ring
+/// containing each hole.
+#[expect(clippy::type_complexity)]
+fn area_parameters(
+    area: Option<&Area>,
+) -> (Option<Vec<String>>, Option<Vec<String>>, Option<Vec<i32>>) {
+    let Some(area) = area else {
+        return (None, None, None);
+    };
+
+    let literal = |ring: &Ring| {
+        let points: Vec<String> = ring
+            .iter()
+            .map(|(longitude, latitude)| format!("({longitude},{latitude})"))
+            .collect();
+
+        format!("({})", points.join(","))
+    };
+
+    let mut exteriors = Vec::new();
+    let mut holes = Vec::new();
+    let mut hole_polygons = Vec::new();
+
+    for (index, (exterior, polygon_holes)) in (1..).zip(&area.polygons) {
+        exteriors.push(literal(exterior));
+
+        for hole in polygon_holes {
+            holes.push(literal(hole));
+            hole_polygons.push(index);
+        }
+    }
+
+    (Some(exteriors), Some(holes), Some(hole_polygons))
+}
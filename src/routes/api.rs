@@ -1,34 +1,82 @@
 use std::cmp;
+use std::time::SystemTime;
 
 use actix_web::{
-    HttpRequest, HttpResponse, Responder, ResponseError, get,
-    http::StatusCode,
-    post,
-    web::{Data, Json, Path, Query},
+    HttpMessage, HttpRequest, HttpResponse, Responder, ResponseError, get,
+    http::{
+        StatusCode,
+        header::{
+            CacheControl, CacheDirective, ETag, EntityTag, HttpDate, IfModifiedSince, IfNoneMatch,
+            LastModified,
+        },
+        uri::PathAndQuery,
+    },
+    mime, post,
+    web::{Bytes, Data, Json, Path, Query},
 };
 use anyhow::Context;
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use futures::TryStreamExt;
 use secrecy::ExposeSecret;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use sqlx::PgPool;
+use sqlx::{Connection, PgConnection, PgPool};
 use time::OffsetDateTime;
 use uuid::Uuid;
+use validator::Validate;
 
+use crate::geo::Area;
+use crate::geofences;
+use crate::history::{Fix, simplify::simplify};
+use crate::live;
 use crate::models::{CreateReportRequest, Device, Report};
+use crate::settings::Settings;
+use crate::signature::{
+    RequestSigner, SIGNATURE_HEADER, SIGNATURE_TIMESTAMP_HEADER, SIGNATURE_VERSION_HEADER,
+    SignatureVersion,
+};
 use crate::util::error_chain_fmt;
 
 #[derive(thiserror::Error)]
 pub enum ApiError {
+    #[error("The report is too old to be submitted with a nonce")]
+    ExpiredNonce,
+    #[error("The signature timestamp is outside of the allowed clock skew")]
+    ExpiredSignature,
+    #[error("The device already has a geofence with the provided name")]
+    GeofenceNameConflict,
+    #[error("The provided credentials were invalid")]
+    InvalidCredentials,
+    #[error("{0}")]
+    InvalidRequest(String),
     #[error("The provided signature was invalid")]
     InvalidSignature,
     #[error("No signature was provided")]
     MissingSignature,
+    #[error("The device associated with the provided API key has no reports")]
+    NoReports,
+    #[error("The request body exceeds the maximum allowed size")]
+    PayloadTooLarge,
+    #[error("The device already has a place with the provided name")]
+    PlaceNameConflict,
+    #[error("The provided report ID or idempotency key is already in use by a different report")]
+    ReportIdConflict,
+    #[error("The provided nonce or signature has already been used")]
+    ReusedNonce,
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
     #[error("There is no device associated with the provided API key")]
     UnknownApiKey,
+    #[error("There is no geofence associated with the provided ID and API key")]
+    UnknownGeofenceId,
+    #[error("There is no import job associated with the provided ID and API key")]
+    UnknownImportJobId,
+    #[error("There is no place associated with the provided ID and API key")]
+    UnknownPlaceId,
     #[error("There is no report associated with the provided ID and API key")]
     UnknownReportId,
+    #[error("Unsigned reports are not enabled for this device")]
+    UnsignedReportsDisabled,
 }
 
 impl std::fmt::Debug for ApiError {
@@ -50,9 +98,25 @@ fn error_response(&self) -> HttpResponse {
 
     fn status_code(&self) -> StatusCode {
         match self {
-            Self::InvalidSignature | Self::MissingSignature => StatusCode::UNAUTHORIZED,
+            Self::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            Self::ExpiredNonce
+            | Self::ExpiredSignature
+            | Self::InvalidCredentials
+            | Self::InvalidSignature
+            | Self::MissingSignature => StatusCode::UNAUTHORIZED,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::GeofenceNameConflict
+            | Self::PlaceNameConflict
+            | Self::ReportIdConflict
+            | Self::ReusedNonce => StatusCode::CONFLICT,
+            Self::UnsignedReportsDisabled => StatusCode::FORBIDDEN,
             Self::UnexpectedError(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            Self::UnknownApiKey | Self::UnknownReportId => StatusCode::NOT_FOUND,
+            Self::NoReports
+            | Self::UnknownApiKey
+            | Self::UnknownGeofenceId
+            | Self::UnknownImportJobId
+            | Self::UnknownPlaceId
+            | Self::UnknownReportId => StatusCode::NOT_FOUND,
         }
     }
 }
@@ -65,6 +129,17 @@ enum Ordering {
     Descending,
 }
 
+/// Returns the range of timestamps to consider, defaulting to all reports up to the present.
+pub(crate) fn time_range(
+    since: Option<OffsetDateTime>,
+    until: Option<OffsetDateTime>,
+) -> (OffsetDateTime, OffsetDateTime) {
+    (
+        since.unwrap_or(OffsetDateTime::UNIX_EPOCH),
+        until.unwrap_or_else(OffsetDateTime::now_utc),
+    )
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ReportParameters {
     #[serde(default, with = "time::serde::iso8601::option")]
@@ -73,6 +148,71 @@ pub struct ReportParameters {
     until: Option<OffsetDateTime>,
     limit: Option<usize>,
     order: Option<Ordering>,
+    cursor: Option<String>,
+    changed_since: Option<i64>,
+    bbox: Option<String>,
+    simplify: Option<f64>,
+    max_points: Option<usize>,
+}
+
+impl ReportParameters {
+    pub(crate) fn time_range(&self) -> (OffsetDateTime, OffsetDateTime) {
+        time_range(self.since, self.until)
+    }
+
+    /// Returns the area within which reports must lie, as given by the bounding box.
+    pub(crate) fn area(&self) -> Result<Option<Area>, ApiError> {
+        self.bbox
+            .as_deref()
+            .map(Area::from_bbox)
+            .transpose()
+            .map_err(ApiError::InvalidRequest)
+    }
+
+    /// Returns the area within which reports must lie, as given by a `GeoJSON` geometry in the body
+    /// of a search request.
+    pub(crate) fn search_area(&self, body: &[u8]) -> Result<Area, ApiError> {
+        if self.bbox.is_some() {
+            return Err(ApiError::InvalidRequest(
+                "bbox cannot be combined with a GeoJSON geometry".to_string(),
+            ));
+        }
+
+        Area::from_geojson(body).map_err(ApiError::InvalidRequest)
+    }
+}
+
+/// An opaque position within a listing of reports, consisting of the timestamp and ID of the last
+/// report of the previous page. As IDs are unique, reports sharing a timestamp are neither skipped
+/// nor repeated.
+struct Cursor {
+    timestamp: OffsetDateTime,
+    id: Uuid,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(32);
+        bytes.extend_from_slice(&self.timestamp.unix_timestamp_nanos().to_be_bytes());
+        bytes.extend_from_slice(self.id.as_bytes());
+
+        BASE64_URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    fn decode(value: &str) -> Result<Self, ApiError> {
+        let invalid = || ApiError::InvalidRequest(format!("Invalid cursor: {value}"));
+
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(value)
+            .map_err(|_| invalid())?;
+        let (timestamp, id) = bytes.split_first_chunk::<16>().ok_or_else(invalid)?;
+
+        Ok(Self {
+            timestamp: OffsetDateTime::from_unix_timestamp_nanos(i128::from_be_bytes(*timestamp))
+                .map_err(|_| invalid())?,
+            id: Uuid::from_slice(id).map_err(|_| invalid())?,
+        })
+    }
 }
 
 #[get("/api/v1/devices/{api_key}/reports/count")]
@@ -87,32 +227,44 @@ pub async fn get_report_count(
         .context("Failed to retrieve the device associated with the provided API key")?
         .ok_or(ApiError::UnknownApiKey)?;
 
-    let since = if let Some(since) = parameters.since {
-        since
-    } else {
-        #[expect(clippy::unwrap_used)]
-        OffsetDateTime::from_unix_timestamp(0).unwrap()
-    };
+    let area = parameters.area()?;
 
-    let until = if let Some(until) = parameters.until {
-        until
-    } else {
-        OffsetDateTime::now_utc()
-    };
+    count_reports(&db, &device, &parameters, area.as_ref()).await
+}
 
-    let count = sqlx::query_scalar!(
-        r#"SELECT COUNT(*) FROM reports WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3"#,
-        device.id,
-        since,
-        until
-    )
-    .fetch_one(&**db)
-    .await
-    .context("Failed to fetch report count for the device associated with the provided API key")?;
+#[post("/api/v1/devices/{api_key}/reports/search/count")]
+#[tracing::instrument(name = "Search report count", skip(db, api_key, body))]
+pub async fn post_report_search_count(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+    parameters: Query<ReportParameters>,
+    body: Bytes,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let area = parameters.search_area(&body)?;
+
+    count_reports(&db, &device, &parameters, Some(&area)).await
+}
+
+async fn count_reports(
+    db: &PgPool,
+    device: &Device,
+    parameters: &ReportParameters,
+    area: Option<&Area>,
+) -> Result<HttpResponse, ApiError> {
+    let (since, until) = parameters.time_range();
 
-    let count_value = count.unwrap_or(0);
+    let count = Report::count(db, device.id, since, until, area)
+        .await
+        .context(
+            "Failed to fetch report count for the device associated with the provided API key",
+        )?;
 
-    Ok(HttpResponse::Ok().json(json!({ "count": count_value})))
+    Ok(HttpResponse::Ok().json(json!({ "count": count })))
 }
 
 #[get("/api/v1/devices/{api_key}/reports/{id}")]
@@ -128,22 +280,93 @@ pub async fn get_report_by_id(
         .context("Failed to retrieve device associated with the provided API key")?
         .ok_or(ApiError::UnknownApiKey)?;
 
-    let report = Report::find_by_id(&db, &id)
+    let report = Report::find_by_id(&db, device.id, &id)
         .await
         .context("Failed to retrieve the report associated with the provided ID and API key")?
         .ok_or(ApiError::UnknownReportId)?;
 
-    if report.device_id == device.id {
-        Ok(Json(report))
+    Ok(Json(report))
+}
+
+#[derive(Serialize)]
+struct LatestReport {
+    report: Report,
+    stale: bool,
+    #[serde(with = "time::serde::iso8601")]
+    stale_at: OffsetDateTime,
+}
+
+#[get("/api/v1/devices/{api_key}/latest")]
+#[tracing::instrument(name = "Get latest report", skip(db, settings, request, api_key))]
+pub async fn get_latest_report(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    api_key: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let report = Report::find_latest(&db, device.id)
+        .await
+        .context("Failed to retrieve the latest report for the device")?
+        .ok_or(ApiError::NoReports)?;
+
+    let stale_at = report.timestamp + settings.live.stale_after();
+    let stale = OffsetDateTime::now_utc() >= stale_at;
+
+    // The response changes when a newer report is submitted, and again when the report becomes
+    // stale.
+    let etag = EntityTag::new_strong(format!("{}-{}", report.id, u8::from(stale)));
+    let submitted = report.submit_timestamp.unwrap_or(report.timestamp);
+    let last_modified = if stale {
+        cmp::max(submitted, stale_at)
+    } else {
+        submitted
+    };
+    // HTTP dates only have a precision of one second.
+    let last_modified = HttpDate::from(SystemTime::from(
+        last_modified.replace_nanosecond(0).unwrap_or(last_modified),
+    ));
+
+    let not_modified = match request.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(items)) => items.iter().any(|item| item.weak_eq(&etag)),
+        None => request
+            .get_header::<IfModifiedSince>()
+            .is_some_and(|since| last_modified <= since.0),
+    };
+
+    let mut response = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+
+    response
+        .insert_header(ETag(etag))
+        .insert_header(LastModified(last_modified))
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]));
+
+    if not_modified {
+        Ok(response.finish())
     } else {
-        Err(ApiError::UnknownReportId)
+        Ok(response.json(LatestReport {
+            report,
+            stale,
+            stale_at,
+        }))
     }
 }
 
 #[get("/api/v1/devices/{api_key}/reports")]
-#[tracing::instrument(name = "Get reports", skip(db, api_key))]
+#[tracing::instrument(name = "Get reports", skip(db, settings, request, api_key))]
 pub async fn get_reports(
     db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
     api_key: Path<String>,
     parameters: Query<ReportParameters>,
 ) -> Result<impl Responder, ApiError> {
@@ -152,108 +375,724 @@ pub async fn get_reports(
         .context("Failed to retrieve the device associated with the provided API key")?
         .ok_or(ApiError::UnknownApiKey)?;
 
-    let ordering = match parameters.order {
-        Some(Ordering::Ascending) => Ordering::Ascending,
-        Some(Ordering::Descending) | None => Ordering::Descending,
-    };
+    let area = parameters.area()?;
+
+    list_reports(
+        &db,
+        &settings,
+        &request,
+        &device,
+        &parameters,
+        area.as_ref(),
+    )
+    .await
+}
 
+#[post("/api/v1/devices/{api_key}/reports/search")]
+#[tracing::instrument(name = "Search reports", skip(db, settings, request, api_key, body))]
+pub async fn post_report_search(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    api_key: Path<String>,
+    parameters: Query<ReportParameters>,
+    body: Bytes,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let area = parameters.search_area(&body)?;
+
+    list_reports(&db, &settings, &request, &device, &parameters, Some(&area)).await
+}
+
+async fn list_reports(
+    db: &PgPool,
+    settings: &Settings,
+    request: &HttpRequest,
+    device: &Device,
+    parameters: &ReportParameters,
+    area: Option<&Area>,
+) -> Result<HttpResponse, ApiError> {
     let limit = if let Some(limit) = parameters.limit {
         cmp::min(10000, limit)
     } else {
         100
     };
 
-    let since = if let Some(since) = parameters.since {
-        since
-    } else {
-        #[expect(clippy::unwrap_used)]
-        OffsetDateTime::from_unix_timestamp(0).unwrap()
-    };
+    let page_size = i64::try_from(limit).unwrap_or(i64::MAX);
 
-    let until = if let Some(until) = parameters.until {
-        until
-    } else {
-        OffsetDateTime::now_utc()
+    if let Some(changed_since) = parameters.changed_since {
+        if parameters.since.is_some()
+            || parameters.until.is_some()
+            || parameters.order.is_some()
+            || parameters.cursor.is_some()
+        {
+            return Err(ApiError::InvalidRequest(
+                "changed_since cannot be combined with since, until, order or cursor".to_string(),
+            ));
+        }
+
+        let reports = Report::find_changed(db, device.id, changed_since, area, page_size)
+            .await
+            .context("Failed to fetch changed reports for the device associated with the provided API key")?;
+
+        let mut response = HttpResponse::Ok();
+
+        if limit > 0
+            && reports.len() == limit
+            && let Some(report) = reports.last()
+        {
+            response.insert_header(next_page_link(
+                request,
+                "changed_since",
+                &report.sequence.to_string(),
+            ));
+        }
+
+        return Ok(response.json(reports));
+    }
+
+    let ordering = match parameters.order {
+        Some(Ordering::Ascending) => Ordering::Ascending,
+        Some(Ordering::Descending) | None => Ordering::Descending,
     };
 
+    if parameters.simplify.is_some() || parameters.max_points.is_some() {
+        return simplified_reports(db, settings, device, parameters, area, &ordering).await;
+    }
+
+    let cursor = parameters
+        .cursor
+        .as_deref()
+        .map(Cursor::decode)
+        .transpose()?
+        .map(|cursor| (cursor.timestamp, cursor.id));
+
+    let (since, until) = parameters.time_range();
+
     let reports = match ordering {
-        Ordering::Ascending => sqlx::query_as!(
-            Report,
-            r#"SELECT * FROM reports WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3 ORDER BY timestamp ASC LIMIT $4"#,
-            device.id,
-            since,
-            until,
-            i64::try_from(limit).unwrap_or(i64::MAX)
-        ).fetch_all(&**db).await,
-        Ordering::Descending => sqlx::query_as!(
-            Report,
-            r#"SELECT * FROM reports WHERE device_id = $1 AND timestamp > $2 AND timestamp < $3 ORDER BY timestamp DESC LIMIT $4"#,
-            device.id,
-            since,
-            until,
-            i64::try_from(limit).unwrap_or(i64::MAX)
-        ).fetch_all(&**db).await,
+        Ordering::Ascending => {
+            Report::find_page(db, device.id, since, until, area, cursor, page_size).await
+        }
+        Ordering::Descending => {
+            Report::find_page_descending(db, device.id, since, until, area, cursor, page_size).await
+        }
     }
     .context("Failed to fetch reports for the device associated with the provided API key")?;
 
+    let mut response = HttpResponse::Ok();
+
+    // A full page may be followed by more reports, which are linked using a cursor positioned
+    // after the last report of the page.
+    if limit > 0
+        && reports.len() == limit
+        && let Some(report) = reports.last()
+    {
+        let cursor = Cursor {
+            timestamp: report.timestamp,
+            id: report.id,
+        };
+
+        response.insert_header(next_page_link(request, "cursor", &cursor.encode()));
+    }
+
+    Ok(response.json(reports))
+}
+
+/// Returns every report within the requested range, reduced to a simplified track. Only the
+/// positions of the reports are held while simplifying, and ranges with more than the configured
+/// number of reports are rejected.
+async fn simplified_reports(
+    db: &PgPool,
+    settings: &Settings,
+    device: &Device,
+    parameters: &ReportParameters,
+    area: Option<&Area>,
+    ordering: &Ordering,
+) -> Result<HttpResponse, ApiError> {
+    if parameters.limit.is_some() || parameters.cursor.is_some() {
+        return Err(ApiError::InvalidRequest(
+            "simplify and max_points cannot be combined with limit or cursor".to_string(),
+        ));
+    }
+
+    if parameters
+        .simplify
+        .is_some_and(|tolerance| !tolerance.is_finite() || tolerance <= 0.0)
+    {
+        return Err(ApiError::InvalidRequest(
+            "simplify must be a positive number of meters".to_string(),
+        ));
+    }
+
+    if parameters
+        .max_points
+        .is_some_and(|max_points| max_points < 2)
+    {
+        return Err(ApiError::InvalidRequest(
+            "max_points must be at least 2".to_string(),
+        ));
+    }
+
+    let max_reports = settings.reports.max_simplified_reports;
+    let mut fixes = Vec::new();
+    let mut ids = Vec::new();
+
+    let (since, until) = parameters.time_range();
+    let mut reports = Report::stream(db, device.id, since, until, area);
+
+    while let Some(report) = reports
+        .try_next()
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?
+    {
+        if fixes.len() == max_reports {
+            return Err(ApiError::InvalidRequest(format!(
+                "At most {max_reports} reports can be simplified, so the range must be narrowed"
+            )));
+        }
+
+        fixes.push(Fix::from(&report));
+        ids.push(report.id);
+    }
+
+    drop(reports);
+
+    let kept: Vec<Uuid> = simplify(&fixes, parameters.simplify, parameters.max_points)
+        .into_iter()
+        .map(|index| ids[index])
+        .collect();
+
+    let mut reports = Report::find_by_ids(db, device.id, &kept)
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?;
+
+    if let Ordering::Descending = ordering {
+        reports.reverse();
+    }
+
     Ok(HttpResponse::Ok().json(reports))
 }
 
+/// Builds a `Link` header to the next page of a listing, repeating the query parameters of the
+/// request with the given parameter replaced.
+fn next_page_link(request: &HttpRequest, name: &str, value: &str) -> (&'static str, String) {
+    let prefix = format!("{name}=");
+
+    let mut query: Vec<&str> = request
+        .query_string()
+        .split('&')
+        .filter(|parameter| !parameter.is_empty() && !parameter.starts_with(&prefix))
+        .collect();
+
+    let parameter = format!("{prefix}{value}");
+    query.push(&parameter);
+
+    (
+        "Link",
+        format!("<{}?{}>; rel=\"next\"", request.path(), query.join("&")),
+    )
+}
+
 #[post("/api/v1/devices/{api_key}/reports")]
-#[tracing::instrument(name = "Post report to device", skip(db, request, api_key))]
+#[tracing::instrument(
+    name = "Post report to device",
+    skip(db, settings, request, api_key, body)
+)]
 pub async fn post_report(
     db: Data<PgPool>,
+    settings: Data<Settings>,
     request: HttpRequest,
     api_key: Path<String>,
-    report_request: actix_web_validator::Json<CreateReportRequest>,
+    body: Bytes,
 ) -> Result<impl Responder, ApiError> {
     let device = Device::find_by_api_key(&db, &api_key)
         .await
         .context("Failed to retrieve the device associated with the provided API key")?
         .ok_or(ApiError::UnknownApiKey)?;
 
-    let signature = request
-        .headers()
-        .get("X-Signature")
-        .ok_or(ApiError::MissingSignature)?
-        .to_str()
-        .map_err(|_| ApiError::InvalidSignature)?;
+    let report_request = parse_report_request(&request, &body)?;
+    let request_signature = verify_request_signature(&request, &body, &device, &settings)?;
 
-    let calculated_signature = report_request
-        .get_signature(device.api_secret.expose_secret())
-        .context("Failed to calculate the expected signature for the provided report")?;
+    if request_signature.is_none() {
+        let signature = get_header(&request, SIGNATURE_HEADER).ok_or(ApiError::MissingSignature)?;
+        verify_signature(&device, &report_request, signature)?;
+    }
+
+    let idempotency_key = get_header(&request, IDEMPOTENCY_KEY_HEADER);
+
+    if idempotency_key.is_some_and(|key| key.is_empty() || key.len() > 255) {
+        return Err(ApiError::InvalidRequest(format!(
+            "The {IDEMPOTENCY_KEY_HEADER} header must be between 1 and 255 characters long"
+        )));
+    }
 
-    if calculated_signature != signature {
-        return Err(ApiError::InvalidSignature);
-    }
-
-    let report =
-        sqlx::query_as!(Report,
-                r#"WITH inserted AS (
-                    INSERT INTO reports (id, device_id, timestamp, submit_timestamp, latitude, longitude, altitude, speed, bearing, accuracy)
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-                    RETURNING *
-                )
-                SELECT * FROM inserted"#,
-            Uuid::new_v4(),
-            device.id,
-            report_request.timestamp,
-            time::OffsetDateTime::now_utc(),
-            report_request.latitude,
-            report_request.longitude,
-            report_request.altitude,
-            report_request.speed,
-            report_request.bearing,
-            report_request.accuracy
-        )
-        .fetch_one(&**db)
+    let mut transaction = db
+        .begin()
         .await
-        .context("Failed to insert report")?;
+        .context("Failed to begin a transaction for the report")?;
+
+    let submission = submit_report(
+        &mut transaction,
+        &device,
+        &report_request,
+        idempotency_key,
+        &settings,
+    )
+    .await?;
+
+    let (status, report) = match submission {
+        Submission::Created(report) => {
+            if let Some(request_signature) = &request_signature {
+                request_signature
+                    .claim(&mut transaction, &device, &settings)
+                    .await?;
+            }
 
-    Ok(HttpResponse::Created()
+            (StatusCode::CREATED, report)
+        }
+        Submission::Duplicate(report) => (StatusCode::OK, report),
+    };
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit the report")?;
+
+    Ok(HttpResponse::build(status)
         .insert_header((
             "Location",
             format!("/api/v1/devices/{api_key}/reports/{}", report.id),
         ))
         .json(report))
 }
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+pub(crate) enum Submission {
+    Created(Report),
+    Duplicate(Report),
+}
+
+/// Inserts a report, or returns the existing report if it has already been submitted. A new
+/// report has its nonce claimed, geofences evaluated and is published to live subscribers, all
+/// within the given transaction, so every source of reports should submit them through here.
+pub(crate) async fn submit_report(
+    db: &mut PgConnection,
+    device: &Device,
+    report_request: &CreateReportRequest,
+    idempotency_key: Option<&str>,
+    settings: &Settings,
+) -> Result<Submission, ApiError> {
+    let report = Report::create(&mut *db, device.id, report_request, idempotency_key)
+        .await
+        .context("Failed to insert report")?;
+
+    if let Some(report) = report {
+        claim_nonce(db, device, report_request, settings).await?;
+
+        geofences::evaluate(db, &report)
+            .await
+            .context("Failed to evaluate geofences for the report")?;
+
+        live::notify(&mut *db, &report)
+            .await
+            .context("Failed to publish the report")?;
+
+        return Ok(Submission::Created(report));
+    }
+
+    let report = Report::find_conflicting(&mut *db, device.id, report_request.id, idempotency_key)
+        .await
+        .context("Failed to retrieve the previously submitted report")?
+        .context("Failed to find the report conflicting with the provided report")?;
+
+    // Version 1 signatures do not cover the ID, so a retry is only recognized if it describes the
+    // same report.
+    if report_request.matches(&report) {
+        Ok(Submission::Duplicate(report))
+    } else {
+        Err(ApiError::ReportIdConflict)
+    }
+}
+
+/// The maximum number of reports accepted in a single batch submission.
+const MAX_BATCH_SIZE: usize = 500;
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum BatchReportResult {
+    Accepted { report: Box<Report> },
+    Duplicate { report: Box<Report> },
+    Rejected { reason: String },
+}
+
+#[post("/api/v1/devices/{api_key}/reports/batch")]
+#[tracing::instrument(
+    name = "Post report batch to device",
+    skip(db, settings, request, api_key, body)
+)]
+pub async fn post_report_batch(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    api_key: Path<String>,
+    body: Bytes,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let request_signature = verify_request_signature(&request, &body, &device, &settings)?;
+
+    let items: Vec<serde_json::Value> = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse report batch: {e}")))?;
+
+    if items.is_empty() || items.len() > MAX_BATCH_SIZE {
+        return Err(ApiError::InvalidRequest(format!(
+            "A report batch must contain between 1 and {MAX_BATCH_SIZE} reports"
+        )));
+    }
+
+    let mut transaction = db
+        .begin()
+        .await
+        .context("Failed to begin a transaction for the report batch")?;
+
+    let mut results = Vec::with_capacity(items.len());
+
+    for item in items {
+        let report_request = match parse_batch_item(&device, &item, request_signature.is_none()) {
+            Ok(report_request) => report_request,
+            Err(e) => {
+                results.push(BatchReportResult::Rejected {
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        // Each report is submitted within a savepoint so that a rejected report is rolled back
+        // without affecting the rest of the batch.
+        let mut savepoint = transaction
+            .begin()
+            .await
+            .context("Failed to create a savepoint for the report")?;
+
+        match submit_report(&mut savepoint, &device, &report_request, None, &settings).await {
+            Ok(Submission::Created(report)) => {
+                savepoint
+                    .commit()
+                    .await
+                    .context("Failed to release the savepoint for the report")?;
+
+                results.push(BatchReportResult::Accepted {
+                    report: Box::new(report),
+                });
+            }
+            Ok(Submission::Duplicate(report)) => {
+                results.push(BatchReportResult::Duplicate {
+                    report: Box::new(report),
+                });
+            }
+            Err(ApiError::UnexpectedError(e)) => return Err(ApiError::UnexpectedError(e)),
+            Err(e) => {
+                results.push(BatchReportResult::Rejected {
+                    reason: e.to_string(),
+                });
+            }
+        }
+    }
+
+    let accepted = results
+        .iter()
+        .filter(|result| matches!(result, BatchReportResult::Accepted { .. }))
+        .count();
+
+    let duplicates = results
+        .iter()
+        .filter(|result| matches!(result, BatchReportResult::Duplicate { .. }))
+        .count();
+
+    // A retried batch consisting entirely of duplicates is allowed to reuse its signature.
+    if accepted > 0
+        && let Some(request_signature) = &request_signature
+    {
+        request_signature
+            .claim(&mut transaction, &device, &settings)
+            .await?;
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit the report batch")?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "accepted": accepted,
+        "duplicates": duplicates,
+        "rejected": results.len() - accepted - duplicates,
+        "results": results,
+    })))
+}
+
+fn parse_batch_item(
+    device: &Device,
+    item: &serde_json::Value,
+    verify_item_signature: bool,
+) -> Result<CreateReportRequest, ApiError> {
+    let report_request = CreateReportRequest::deserialize(item)
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+
+    validate_report_request(&report_request)?;
+
+    if verify_item_signature {
+        let signature = item
+            .get("signature")
+            .ok_or(ApiError::MissingSignature)?
+            .as_str()
+            .ok_or(ApiError::InvalidSignature)?;
+
+        verify_signature(device, &report_request, signature)?;
+    }
+
+    Ok(report_request)
+}
+
+/// Parses and validates a report submitted as a JSON body, as the validating JSON extractor did
+/// before the raw body was needed to verify version 2 signatures.
+fn parse_report_request(
+    request: &HttpRequest,
+    body: &[u8],
+) -> Result<CreateReportRequest, ApiError> {
+    let is_json = request
+        .mime_type()
+        .ok()
+        .flatten()
+        .is_some_and(|mime| mime.essence_str() == mime::APPLICATION_JSON.essence_str());
+
+    if !is_json {
+        return Err(ApiError::InvalidRequest(
+            "The content type must be application/json".to_string(),
+        ));
+    }
+
+    let report_request: CreateReportRequest = serde_json::from_slice(body)
+        .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse report: {e}")))?;
+
+    validate_report_request(&report_request)?;
+
+    Ok(report_request)
+}
+
+pub(crate) fn validate_report_request(
+    report_request: &CreateReportRequest,
+) -> Result<(), ApiError> {
+    report_request
+        .validate()
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))
+}
+
+pub(crate) fn get_header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn verify_signature(
+    device: &Device,
+    report_request: &CreateReportRequest,
+    signature: &str,
+) -> Result<(), ApiError> {
+    let valid = report_request
+        .verify_signature(device.api_secret.expose_secret(), signature)
+        .context("Failed to calculate the expected signature for the provided report")?;
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::InvalidSignature)
+    }
+}
+
+/// A verified version 2 request signature. Each signature may only be used once.
+pub(crate) struct RequestSignature {
+    signature: String,
+    timestamp: OffsetDateTime,
+}
+
+impl RequestSignature {
+    pub(crate) async fn claim(
+        &self,
+        db: &mut PgConnection,
+        device: &Device,
+        settings: &Settings,
+    ) -> Result<(), ApiError> {
+        if device
+            .claim_nonce(
+                db,
+                &format!("v2:{}", self.signature),
+                self.timestamp,
+                settings.security.nonce_retention(),
+            )
+            .await
+            .context("Failed to record the signature for the provided request")?
+        {
+            Ok(())
+        } else {
+            Err(ApiError::ReusedNonce)
+        }
+    }
+}
+
+/// The headers of a version 2 request signature, which has yet to be checked against the body.
+pub(crate) struct PendingRequestSignature {
+    signature: String,
+    timestamp: OffsetDateTime,
+    signer: RequestSigner,
+}
+
+impl PendingRequestSignature {
+    pub(crate) fn update(&mut self, body: &[u8]) {
+        self.signer.update(body);
+    }
+
+    pub(crate) fn verify(self) -> Result<RequestSignature, ApiError> {
+        if self.signer.verify(&self.signature) {
+            // Hexadecimal signatures are accepted in either case, so the signature is normalized
+            // before being claimed to prevent replaying a request by changing its case.
+            Ok(RequestSignature {
+                signature: self.signature.to_ascii_lowercase(),
+                timestamp: self.timestamp,
+            })
+        } else {
+            Err(ApiError::InvalidSignature)
+        }
+    }
+}
+
+/// Checks the headers of a version 2 request signature, allowing the body to be verified as it is
+/// streamed.
+///
+/// Version 1 signatures cover individual reports rather than the request, so `None` is returned
+/// for such requests and the caller is responsible for verifying each report.
+pub(crate) fn begin_request_signature(
+    request: &HttpRequest,
+    device: &Device,
+    settings: &Settings,
+) -> Result<Option<PendingRequestSignature>, ApiError> {
+    let version = match get_header(request, SIGNATURE_VERSION_HEADER) {
+        Some(version) => SignatureVersion::try_from(version).map_err(ApiError::InvalidRequest)?,
+        None => SignatureVersion::V1,
+    };
+
+    if version == SignatureVersion::V1 {
+        return Ok(None);
+    }
+
+    let signature = get_header(request, SIGNATURE_HEADER).ok_or(ApiError::MissingSignature)?;
+    let timestamp_header =
+        get_header(request, SIGNATURE_TIMESTAMP_HEADER).ok_or(ApiError::InvalidSignature)?;
+
+    let timestamp = timestamp_header
+        .parse::<i64>()
+        .ok()
+        .and_then(|timestamp| OffsetDateTime::from_unix_timestamp(timestamp).ok())
+        .ok_or(ApiError::InvalidSignature)?;
+
+    if (OffsetDateTime::now_utc() - timestamp).abs() > settings.security.signature_clock_skew() {
+        return Err(ApiError::ExpiredSignature);
+    }
+
+    let path = request
+        .uri()
+        .path_and_query()
+        .map_or_else(|| request.path(), PathAndQuery::as_str);
+
+    let signer = RequestSigner::new(
+        device.api_secret.expose_secret(),
+        request.method().as_str(),
+        path,
+        timestamp_header,
+    )
+    .context("Failed to verify the signature for the provided request")?;
+
+    Ok(Some(PendingRequestSignature {
+        signature: signature.to_string(),
+        timestamp,
+        signer,
+    }))
+}
+
+/// Verifies the signature of a version 2 request.
+///
+/// As with [`begin_request_signature`], `None` is returned for version 1 requests.
+pub(crate) fn verify_request_signature(
+    request: &HttpRequest,
+    body: &[u8],
+    device: &Device,
+    settings: &Settings,
+) -> Result<Option<RequestSignature>, ApiError> {
+    begin_request_signature(request, device, settings)?
+        .map(|mut signature| {
+            signature.update(body);
+            signature.verify()
+        })
+        .transpose()
+}
+
+/// Verifies and claims the version 2 signature of a request, which is required for requests
+/// changing the configuration of a device.
+pub(crate) async fn claim_request_signature(
+    db: &PgPool,
+    request: &HttpRequest,
+    body: &[u8],
+    device: &Device,
+    settings: &Settings,
+) -> Result<(), ApiError> {
+    let signature =
+        verify_request_signature(request, body, device, settings)?.ok_or_else(|| {
+            ApiError::InvalidRequest(
+                "This request must be signed with a version 2 signature".into(),
+            )
+        })?;
+
+    let mut connection = db
+        .acquire()
+        .await
+        .context("Failed to acquire a database connection")?;
+
+    signature.claim(&mut connection, device, settings).await
+}
+
+async fn claim_nonce(
+    db: &mut PgConnection,
+    device: &Device,
+    report_request: &CreateReportRequest,
+    settings: &Settings,
+) -> Result<(), ApiError> {
+    let Some(nonce) = &report_request.nonce else {
+        return Ok(());
+    };
+
+    let retention = settings.security.nonce_retention();
+
+    if report_request.timestamp < OffsetDateTime::now_utc() - retention {
+        return Err(ApiError::ExpiredNonce);
+    }
+
+    if device
+        .claim_nonce(db, nonce, report_request.timestamp, retention)
+        .await
+        .context("Failed to record the nonce for the provided report")?
+    {
+        Ok(())
+    } else {
+        Err(ApiError::ReusedNonce)
+    }
+}
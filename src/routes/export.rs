@@ -0,0 +1,148 @@
+use actix_web::{
+    HttpResponse, Responder, get,
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    post,
+    web::{Bytes, Data, Path, Query},
+};
+use anyhow::Context;
+use futures::stream;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::formats::gpx::GpxWriter;
+use crate::geo::Area;
+use crate::models::{Device, Report};
+use crate::routes::api::{ApiError, ReportParameters};
+
+/// The number of reports fetched from the database for each chunk of an export.
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+/// Reports separated by more than this are placed in separate track segments.
+const SEGMENT_GAP: Duration = Duration::minutes(2);
+
+struct GpxExport {
+    db: PgPool,
+    device_id: Uuid,
+    since: OffsetDateTime,
+    until: OffsetDateTime,
+    area: Option<Area>,
+    after: Option<(OffsetDateTime, Uuid)>,
+    writer: GpxWriter,
+    finished: bool,
+}
+
+impl GpxExport {
+    /// Writes the next page of reports, including the footer once all reports have been written.
+    async fn next_chunk(&mut self) -> Result<Bytes, ApiError> {
+        let reports = Report::find_page(
+            &self.db,
+            self.device_id,
+            self.since,
+            self.until,
+            self.area.as_ref(),
+            self.after,
+            EXPORT_PAGE_SIZE,
+        )
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?;
+
+        let mut chunk = String::new();
+
+        for report in &reports {
+            self.writer.write(&mut chunk, report);
+        }
+
+        if let Some(report) = reports.last() {
+            self.after = Some((report.timestamp, report.id));
+        }
+
+        if reports.len() < usize::try_from(EXPORT_PAGE_SIZE).unwrap_or(usize::MAX) {
+            chunk.push_str(&self.writer.footer());
+            self.finished = true;
+        }
+
+        Ok(Bytes::from(chunk))
+    }
+}
+
+#[get("/api/v1/devices/{api_key}/reports.gpx")]
+#[tracing::instrument(name = "Export reports as GPX", skip(db, api_key))]
+pub async fn get_reports_gpx(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+    parameters: Query<ReportParameters>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let area = parameters.area()?;
+
+    Ok(export_gpx(&db, device, &parameters, area))
+}
+
+#[post("/api/v1/devices/{api_key}/reports/search.gpx")]
+#[tracing::instrument(name = "Export searched reports as GPX", skip(db, api_key, body))]
+pub async fn post_report_search_gpx(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+    parameters: Query<ReportParameters>,
+    body: Bytes,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let area = parameters.search_area(&body)?;
+
+    Ok(export_gpx(&db, device, &parameters, Some(area)))
+}
+
+fn export_gpx(
+    db: &PgPool,
+    device: Device,
+    parameters: &ReportParameters,
+    area: Option<Area>,
+) -> HttpResponse {
+    let (since, until) = parameters.time_range();
+
+    let export = GpxExport {
+        db: db.clone(),
+        device_id: device.id,
+        since,
+        until,
+        area,
+        after: None,
+        writer: GpxWriter::new(SEGMENT_GAP),
+        finished: false,
+    };
+
+    let filename = format!("{}.gpx", device.api_key);
+    let header = stream::once(async move { Ok(Bytes::from(GpxWriter::header(&device.api_key))) });
+
+    let body = stream::unfold(export, |mut export| async move {
+        if export.finished {
+            return None;
+        }
+
+        let chunk = export.next_chunk().await;
+
+        // Stop after an error, as the document can no longer be completed.
+        if chunk.is_err() {
+            export.finished = true;
+        }
+
+        Some((chunk, export))
+    });
+
+    HttpResponse::Ok()
+        .content_type("application/gpx+xml")
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(filename)],
+        })
+        .streaming(futures::StreamExt::chain(header, body))
+}
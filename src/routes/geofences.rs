@@ -0,0 +1,157 @@
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, delete, get,
+    http::StatusCode,
+    post,
+    web::{Bytes, Data, Path, Query},
+};
+use anyhow::Context;
+use serde::Deserialize;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::geo::Area;
+use crate::models::{CreateGeofenceRequest, Device, Geofence, GeofenceEvent};
+use crate::routes::api::{ApiError, claim_request_signature, time_range};
+use crate::settings::Settings;
+
+impl CreateGeofenceRequest {
+    /// Checks that the request describes either a circle or a polygon.
+    fn validate_shape(&self) -> Result<(), ApiError> {
+        match (self.latitude, self.longitude, self.radius, &self.geometry) {
+            (Some(_), Some(_), Some(_), None) => Ok(()),
+            (None, None, None, Some(geometry)) => {
+                let geometry = serde_json::to_vec(geometry)
+                    .context("Failed to serialize the geofence geometry")?;
+
+                Area::from_geojson(&geometry).map_err(ApiError::InvalidRequest)?;
+
+                Ok(())
+            }
+            _ => Err(ApiError::InvalidRequest(
+                "A geofence must have either a latitude, longitude and radius, or a geometry"
+                    .to_string(),
+            )),
+        }
+    }
+}
+
+#[post("/api/v1/devices/{api_key}/geofences")]
+#[tracing::instrument(
+    name = "Post geofence to device",
+    skip(db, settings, request, api_key, body)
+)]
+pub async fn post_geofence(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    api_key: Path<String>,
+    body: Bytes,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    claim_request_signature(&db, &request, &body, &device, &settings).await?;
+
+    let geofence_request: CreateGeofenceRequest = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse geofence: {e}")))?;
+
+    geofence_request
+        .validate()
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+
+    geofence_request.validate_shape()?;
+
+    let hysteresis = geofence_request
+        .hysteresis
+        .unwrap_or_else(|| f64::from(settings.geofences.hysteresis_meters));
+
+    let geofence = Geofence::create(&db, device.id, &geofence_request, hysteresis)
+        .await
+        .context("Failed to insert geofence")?
+        .ok_or(ApiError::GeofenceNameConflict)?;
+
+    Ok(HttpResponse::build(StatusCode::CREATED)
+        .insert_header((
+            "Location",
+            format!("/api/v1/devices/{api_key}/geofences/{}", geofence.id),
+        ))
+        .json(geofence))
+}
+
+#[get("/api/v1/devices/{api_key}/geofences")]
+#[tracing::instrument(name = "Get geofences", skip(db, api_key))]
+pub async fn get_geofences(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let geofences = Geofence::find_by_device(&db, device.id).await.context(
+        "Failed to retrieve geofences for the device associated with the provided API key",
+    )?;
+
+    Ok(HttpResponse::Ok().json(geofences))
+}
+
+#[delete("/api/v1/devices/{api_key}/geofences/{id}")]
+#[tracing::instrument(name = "Delete geofence", skip(db, settings, request, path))]
+pub async fn delete_geofence(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    path: Path<(String, Uuid)>,
+) -> Result<impl Responder, ApiError> {
+    let (api_key, id) = path.into_inner();
+
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    claim_request_signature(&db, &request, &[], &device, &settings).await?;
+
+    if !Geofence::delete(&db, device.id, id)
+        .await
+        .context("Failed to delete geofence")?
+    {
+        return Err(ApiError::UnknownGeofenceId);
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GeofenceEventParameters {
+    #[serde(default, with = "time::serde::iso8601::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    until: Option<OffsetDateTime>,
+}
+
+#[get("/api/v1/devices/{api_key}/geofences/events")]
+#[tracing::instrument(name = "Get geofence events", skip(db, api_key))]
+pub async fn get_geofence_events(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+    parameters: Query<GeofenceEventParameters>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let (since, until) = time_range(parameters.since, parameters.until);
+
+    let events = GeofenceEvent::find_range(&db, device.id, since, until)
+        .await
+        .context("Failed to retrieve geofence events for the device")?;
+
+    Ok(HttpResponse::Ok().json(events))
+}
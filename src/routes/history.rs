@@ -0,0 +1,568 @@
+use std::collections::HashMap;
+
+use actix_web::{
+    HttpResponse, Responder, get,
+    web::{Data, Path, Query},
+};
+use anyhow::Context;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use time::{Date, Duration, OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::history::colocation::find_colocations;
+use crate::history::proximity::ProximitySearch;
+use crate::history::statistics::{Statistics, StatisticsSummary};
+use crate::history::stays::{StayDetector, StayThresholds};
+use crate::history::summaries::ActivitySummarizer;
+use crate::history::trips::{Bounds, Trip, TripSegmenter, TripThresholds};
+use crate::models::{DailySummary, Day, Device, Report};
+use crate::routes::api::{ApiError, time_range};
+use crate::settings::Settings;
+
+/// The default maximum interval between reports across which a position is interpolated.
+const DEFAULT_MAX_GAP: Duration = Duration::minutes(5);
+
+#[derive(Deserialize, Debug)]
+pub struct ProximityParameters {
+    latitude: f64,
+    longitude: f64,
+    radius: f64,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    until: Option<OffsetDateTime>,
+}
+
+impl ProximityParameters {
+    fn validate(&self) -> Result<(), ApiError> {
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(ApiError::InvalidRequest(format!(
+                "Invalid position: {},{}",
+                self.latitude, self.longitude
+            )));
+        }
+
+        if !self.radius.is_finite() || self.radius <= 0.0 {
+            return Err(ApiError::InvalidRequest(
+                "The radius must be a positive number of meters".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[get("/api/v1/devices/{api_key}/proximity")]
+#[tracing::instrument(name = "Get proximity intervals", skip(db, api_key))]
+pub async fn get_proximity(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+    parameters: Query<ProximityParameters>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    parameters.validate()?;
+
+    let (since, until) = time_range(parameters.since, parameters.until);
+
+    let mut search =
+        ProximitySearch::new(parameters.latitude, parameters.longitude, parameters.radius);
+    let mut reports = Report::stream_near(
+        &db,
+        device.id,
+        since,
+        until,
+        (parameters.latitude, parameters.longitude),
+        parameters.radius,
+    );
+
+    while let Some(report) = reports
+        .try_next()
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?
+    {
+        search.push(&report);
+    }
+
+    Ok(HttpResponse::Ok().json(search.finish()))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ColocationParameters {
+    distance: f64,
+    max_gap: Option<i64>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    until: Option<OffsetDateTime>,
+}
+
+impl ColocationParameters {
+    fn max_gap(&self) -> Result<Duration, ApiError> {
+        match self.max_gap {
+            Some(seconds) if seconds <= 0 => Err(ApiError::InvalidRequest(
+                "The maximum gap must be a positive number of seconds".to_string(),
+            )),
+            Some(seconds) => Ok(Duration::seconds(seconds)),
+            None => Ok(DEFAULT_MAX_GAP),
+        }
+    }
+}
+
+#[get("/api/v1/devices/{api_key}/colocation/{other_api_key}")]
+#[tracing::instrument(name = "Get colocation intervals", skip(db, path))]
+pub async fn get_colocation(
+    db: Data<PgPool>,
+    path: Path<(String, String)>,
+    parameters: Query<ColocationParameters>,
+) -> Result<impl Responder, ApiError> {
+    let (api_key, other_api_key) = path.into_inner();
+
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let other_device = Device::find_by_api_key(&db, &other_api_key)
+        .await
+        .context("Failed to retrieve the device associated with the other API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    if !parameters.distance.is_finite() || parameters.distance <= 0.0 {
+        return Err(ApiError::InvalidRequest(
+            "The distance must be a positive number of meters".to_string(),
+        ));
+    }
+
+    let max_gap = parameters.max_gap()?;
+    let (since, until) = time_range(parameters.since, parameters.until);
+
+    let intervals = find_colocations(
+        &db,
+        (device.id, other_device.id),
+        (since, until),
+        parameters.distance,
+        max_gap,
+    )
+    .await
+    .context("Failed to fetch reports for the devices associated with the provided API keys")?;
+
+    Ok(HttpResponse::Ok().json(intervals))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TripParameters {
+    gap: Option<u32>,
+    stationary: Option<u32>,
+    stationary_radius: Option<u32>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    until: Option<OffsetDateTime>,
+}
+
+impl TripParameters {
+    /// Returns the thresholds for dividing reports into trips, defaulting to those configured.
+    fn thresholds(&self, settings: &Settings) -> Result<TripThresholds, ApiError> {
+        let seconds = |value: Option<u32>, default: Duration| {
+            value.map_or(default, |value| Duration::seconds(i64::from(value)))
+        };
+
+        let thresholds = TripThresholds {
+            gap: seconds(self.gap, settings.trips.gap()),
+            stationary: seconds(self.stationary, settings.trips.stationary()),
+            stationary_radius: f64::from(
+                self.stationary_radius
+                    .unwrap_or(settings.trips.stationary_radius_meters),
+            ),
+        };
+
+        if thresholds.gap.is_zero() || thresholds.stationary.is_zero() {
+            return Err(ApiError::InvalidRequest(
+                "The gap and stationary thresholds must be positive".to_string(),
+            ));
+        }
+
+        Ok(thresholds)
+    }
+}
+
+#[derive(Serialize)]
+struct TripSummary {
+    #[serde(with = "time::serde::iso8601")]
+    start: OffsetDateTime,
+    #[serde(with = "time::serde::iso8601")]
+    end: OffsetDateTime,
+    bbox: Bounds,
+    reports_url: String,
+    statistics: StatisticsSummary,
+}
+
+impl TripSummary {
+    fn new(api_key: &str, trip: &Trip) -> Result<Self, ApiError> {
+        // The bounds of a report listing are exclusive, and timestamps are stored with microsecond
+        // precision, so these include exactly the reports of the trip.
+        let since = (trip.start.timestamp - Duration::microseconds(1))
+            .format(&Rfc3339)
+            .context("Failed to format the start of the trip")?;
+        let until = (trip.end.timestamp + Duration::microseconds(1))
+            .format(&Rfc3339)
+            .context("Failed to format the end of the trip")?;
+
+        Ok(Self {
+            start: trip.start.timestamp,
+            end: trip.end.timestamp,
+            bbox: trip.bounds,
+            reports_url: format!(
+                "/api/v1/devices/{api_key}/reports?order=asc&since={since}&until={until}"
+            ),
+            statistics: trip.statistics.summary(),
+        })
+    }
+}
+
+#[get("/api/v1/devices/{api_key}/trips")]
+#[tracing::instrument(name = "Get trips", skip(db, settings, api_key))]
+pub async fn get_trips(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    api_key: Path<String>,
+    parameters: Query<TripParameters>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let thresholds = parameters.thresholds(&settings)?;
+    let (since, until) = time_range(parameters.since, parameters.until);
+
+    let mut segmenter = TripSegmenter::new(thresholds);
+    let mut reports = Report::stream(&db, device.id, since, until, None);
+
+    while let Some(report) = reports
+        .try_next()
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?
+    {
+        segmenter.push(&report);
+    }
+
+    let trips = segmenter
+        .finish()
+        .iter()
+        .map(|trip| TripSummary::new(&device.api_key, trip))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(HttpResponse::Ok().json(trips))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StatisticsParameters {
+    #[serde(default, with = "time::serde::iso8601::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    until: Option<OffsetDateTime>,
+}
+
+#[get("/api/v1/devices/{api_key}/statistics")]
+#[tracing::instrument(name = "Get statistics", skip(db, api_key))]
+pub async fn get_statistics(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+    parameters: Query<StatisticsParameters>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let (since, until) = time_range(parameters.since, parameters.until);
+
+    let mut statistics = Statistics::default();
+    let mut reports = Report::stream(&db, device.id, since, until, None);
+
+    while let Some(report) = reports
+        .try_next()
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?
+    {
+        statistics.push(&report);
+    }
+
+    Ok(HttpResponse::Ok().json(statistics.summary()))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StayParameters {
+    radius: Option<u32>,
+    duration: Option<u32>,
+    max_accuracy: Option<u32>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    until: Option<OffsetDateTime>,
+}
+
+impl StayParameters {
+    /// Returns the thresholds for detecting stays, defaulting to those configured.
+    fn thresholds(&self, settings: &Settings) -> Result<StayThresholds, ApiError> {
+        let thresholds = StayThresholds {
+            radius: f64::from(self.radius.unwrap_or(settings.stays.radius_meters)),
+            duration: self.duration.map_or(settings.stays.duration(), |duration| {
+                Duration::seconds(i64::from(duration))
+            }),
+            max_accuracy: f64::from(
+                self.max_accuracy
+                    .unwrap_or(settings.stays.max_accuracy_meters),
+            ),
+        };
+
+        if thresholds.radius <= 0.0 || thresholds.duration.is_zero() {
+            return Err(ApiError::InvalidRequest(
+                "The radius and duration thresholds must be positive".to_string(),
+            ));
+        }
+
+        Ok(thresholds)
+    }
+}
+
+#[get("/api/v1/devices/{api_key}/stays")]
+#[tracing::instrument(name = "Get stays", skip(db, settings, api_key))]
+pub async fn get_stays(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    api_key: Path<String>,
+    parameters: Query<StayParameters>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let thresholds = parameters.thresholds(&settings)?;
+    let (since, until) = time_range(parameters.since, parameters.until);
+
+    let mut detector = StayDetector::new(thresholds);
+    let mut reports = Report::stream(&db, device.id, since, until, None);
+
+    while let Some(report) = reports
+        .try_next()
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?
+    {
+        detector.push(&report);
+    }
+
+    Ok(HttpResponse::Ok().json(detector.finish()))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SummaryParameters {
+    from: Date,
+    to: Date,
+    tz: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DaySummary {
+    date: Date,
+    reports: i64,
+    distance: f64,
+    moving_time: f64,
+    trips: i64,
+    stays: i64,
+    #[serde(with = "time::serde::iso8601::option")]
+    first_report: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::iso8601::option")]
+    last_report: Option<OffsetDateTime>,
+    bbox: Option<[f64; 4]>,
+}
+
+impl From<&DailySummary> for DaySummary {
+    fn from(summary: &DailySummary) -> Self {
+        let bbox = match (
+            summary.min_longitude,
+            summary.min_latitude,
+            summary.max_longitude,
+            summary.max_latitude,
+        ) {
+            (Some(min_longitude), Some(min_latitude), Some(max_longitude), Some(max_latitude)) => {
+                Some([min_longitude, min_latitude, max_longitude, max_latitude])
+            }
+            _ => None,
+        };
+
+        Self {
+            date: summary.day,
+            reports: summary.reports,
+            distance: summary.distance,
+            moving_time: summary.moving_time,
+            trips: summary.trips,
+            stays: summary.stays,
+            first_report: summary.first_report,
+            last_report: summary.last_report,
+            bbox,
+        }
+    }
+}
+
+/// Returns the thresholds used to summarize activity.
+fn activity_thresholds(settings: &Settings) -> (TripThresholds, StayThresholds) {
+    let trip_thresholds = TripThresholds {
+        gap: settings.trips.gap(),
+        stationary: settings.trips.stationary(),
+        stationary_radius: f64::from(settings.trips.stationary_radius_meters),
+    };
+
+    (trip_thresholds, StayThresholds::from(&settings.stays))
+}
+
+/// Describes the thresholds used to summarize activity, so that cached summaries computed with
+/// other thresholds are recognized.
+fn describe_activity_thresholds(settings: &Settings) -> String {
+    let (trip_thresholds, stay_thresholds) = activity_thresholds(settings);
+
+    format!("{trip_thresholds:?} {stay_thresholds:?}")
+}
+
+/// Summarizes the reports of a device within a day.
+async fn summarize_day(
+    db: &PgPool,
+    device: &Device,
+    timezone: &str,
+    day: &Day,
+    settings: &Settings,
+) -> Result<DailySummary, ApiError> {
+    let (trip_thresholds, stay_thresholds) = activity_thresholds(settings);
+    let mut summarizer = ActivitySummarizer::new(trip_thresholds, stay_thresholds);
+
+    // The start of the day is inclusive, while the range of reports is exclusive.
+    let mut reports = Report::stream(
+        db,
+        device.id,
+        day.start - Duration::microseconds(1),
+        day.end,
+        None,
+    );
+
+    while let Some(report) = reports
+        .try_next()
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?
+    {
+        summarizer.push(&report);
+    }
+
+    let activity = summarizer.finish();
+
+    Ok(DailySummary {
+        device_id: device.id,
+        timezone: timezone.to_string(),
+        day: day.day,
+        thresholds: describe_activity_thresholds(settings),
+        start_timestamp: day.start,
+        end_timestamp: day.end,
+        reports: i64::try_from(activity.reports).unwrap_or(i64::MAX),
+        distance: activity.distance,
+        moving_time: activity.moving_time,
+        trips: i64::try_from(activity.trips).unwrap_or(i64::MAX),
+        stays: i64::try_from(activity.stays).unwrap_or(i64::MAX),
+        first_report: activity.first_report,
+        last_report: activity.last_report,
+        min_latitude: activity.bounds.map(|bounds| bounds.min_latitude),
+        min_longitude: activity.bounds.map(|bounds| bounds.min_longitude),
+        max_latitude: activity.bounds.map(|bounds| bounds.max_latitude),
+        max_longitude: activity.bounds.map(|bounds| bounds.max_longitude),
+    })
+}
+
+#[get("/api/v1/devices/{api_key}/summaries")]
+#[tracing::instrument(name = "Get daily summaries", skip(db, settings, api_key))]
+pub async fn get_summaries(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    api_key: Path<String>,
+    parameters: Query<SummaryParameters>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let timezone = parameters
+        .tz
+        .as_deref()
+        .unwrap_or(&settings.summaries.timezone);
+
+    let days = (parameters.to - parameters.from).whole_days() + 1;
+
+    if days < 1 || days > i64::from(settings.summaries.max_days) {
+        return Err(ApiError::InvalidRequest(format!(
+            "The range must include between 1 and {} days",
+            settings.summaries.max_days
+        )));
+    }
+
+    if !DailySummary::timezone_exists(&db, timezone)
+        .await
+        .context("Failed to check the time zone")?
+    {
+        return Err(ApiError::InvalidRequest(format!(
+            "Unknown time zone: {timezone}"
+        )));
+    }
+
+    // Read before summarizing any day, so that summaries are only cached if no report could have
+    // been missed.
+    let sequence = device
+        .last_report_sequence(&db)
+        .await
+        .context("Failed to retrieve the report sequence of the device")?;
+
+    // Summaries computed with other thresholds are ignored, and replaced once recomputed.
+    let thresholds = describe_activity_thresholds(&settings);
+
+    let mut cached: HashMap<Date, DailySummary> = DailySummary::find_range(
+        &db,
+        device.id,
+        timezone,
+        &thresholds,
+        parameters.from,
+        parameters.to,
+    )
+    .await
+    .context("Failed to retrieve daily summaries for the device")?
+    .into_iter()
+    .map(|summary| (summary.day, summary))
+    .collect();
+
+    let mut summaries = Vec::new();
+
+    for day in DailySummary::days(&db, parameters.from, parameters.to, timezone)
+        .await
+        .context("Failed to determine the days within the range")?
+    {
+        let summary = if let Some(summary) = cached.remove(&day.day) {
+            summary
+        } else {
+            let summary = summarize_day(&db, &device, timezone, &day, &settings).await?;
+
+            summary
+                .create_if_unchanged(&db, sequence)
+                .await
+                .context("Failed to cache the daily summary")?;
+
+            summary
+        };
+
+        summaries.push(DaySummary::from(&summary));
+    }
+
+    Ok(HttpResponse::Ok().json(summaries))
+}
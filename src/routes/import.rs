@@ -0,0 +1,266 @@
+use std::fs::File;
+use std::io::Write;
+
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, get,
+    http::header,
+    post,
+    web::{self, BytesMut, Data, Path, Payload},
+};
+use anyhow::Context;
+use futures::StreamExt;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::formats;
+use crate::import;
+use crate::models::{Device, ImportJob};
+use crate::routes::api::{
+    ApiError, PendingRequestSignature, begin_request_signature, verify_request_signature,
+};
+use crate::settings::Settings;
+
+/// Reads a request body of up to the given number of bytes.
+pub(crate) async fn read_payload(mut payload: Payload, limit: usize) -> Result<BytesMut, ApiError> {
+    let mut body = BytesMut::new();
+
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+
+        if body.len() + chunk.len() > limit {
+            return Err(ApiError::PayloadTooLarge);
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+#[post("/api/v1/devices/{api_key}/import")]
+#[tracing::instrument(
+    name = "Import track file",
+    skip(db, settings, request, api_key, payload)
+)]
+pub async fn post_import(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    api_key: Path<String>,
+    payload: Payload,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let body = read_payload(payload, settings.import.max_upload_bytes).await?;
+
+    // Version 1 signatures only cover individual reports, so imports require a version 2 signature.
+    let signature =
+        verify_request_signature(&request, &body, &device, &settings)?.ok_or_else(|| {
+            ApiError::InvalidRequest("Imports must be signed with a version 2 signature".into())
+        })?;
+
+    let mut connection = db
+        .acquire()
+        .await
+        .context("Failed to acquire a database connection")?;
+
+    signature.claim(&mut connection, &device, &settings).await?;
+    drop(connection);
+
+    let track = web::block(move || {
+        let document = std::str::from_utf8(&body)?;
+        formats::parse_track(document)
+    })
+    .await
+    .context("Failed to run the track parser")?
+    .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse track file: {e}")))?;
+
+    let summary = import::import_track(&db, device.id, track)
+        .await
+        .context("Failed to import track file")?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// An uploaded file that is removed when dropped, unless it has been kept. This ensures rejected,
+/// failed, and abandoned uploads do not remain on disk.
+struct UploadFile {
+    path: std::path::PathBuf,
+    keep: bool,
+}
+
+impl UploadFile {
+    fn new(path: std::path::PathBuf) -> Self {
+        Self { path, keep: false }
+    }
+
+    fn keep(mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for UploadFile {
+    fn drop(&mut self) {
+        if self.keep {
+            return;
+        }
+
+        if let Err(e) = std::fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!("Failed to remove rejected import file: {e}");
+        }
+    }
+}
+
+/// Writes a request body of up to the given number of bytes to a file, while checking its
+/// signature. Returns the number of bytes written.
+async fn write_payload(
+    mut payload: Payload,
+    path: std::path::PathBuf,
+    signature: &mut PendingRequestSignature,
+    limit: u64,
+) -> Result<u64, ApiError> {
+    let mut file = web::block(move || {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        File::create(path)
+    })
+    .await
+    .context("Failed to run the file writer")?
+    .context("Failed to create the import file")?;
+
+    let mut size = 0;
+
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+
+        size += chunk.len() as u64;
+
+        if size > limit {
+            return Err(ApiError::PayloadTooLarge);
+        }
+
+        signature.update(&chunk);
+
+        file = web::block(move || file.write_all(&chunk).map(|()| file))
+            .await
+            .context("Failed to run the file writer")?
+            .context("Failed to write the import file")?;
+    }
+
+    web::block(move || file.sync_all())
+        .await
+        .context("Failed to run the file writer")?
+        .context("Failed to write the import file")?;
+
+    Ok(size)
+}
+
+#[post("/api/v1/devices/{api_key}/imports/takeout")]
+#[tracing::instrument(
+    name = "Upload location history",
+    skip(db, settings, request, api_key, payload)
+)]
+pub async fn post_takeout_import(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    api_key: Path<String>,
+    payload: Payload,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    // The body is too large to hold in memory, so its signature is checked as it is written.
+    let mut signature =
+        begin_request_signature(&request, &device, &settings)?.ok_or_else(|| {
+            ApiError::InvalidRequest("Imports must be signed with a version 2 signature".into())
+        })?;
+
+    // Reject bodies declared to be too large before writing anything to disk.
+    let declared_size = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    if declared_size.is_some_and(|size| size > settings.import.max_takeout_bytes) {
+        return Err(ApiError::PayloadTooLarge);
+    }
+
+    let id = Uuid::new_v4();
+    let path = std::path::Path::new(&settings.import.directory).join(format!("{id}.json"));
+    let file = UploadFile::new(path.clone());
+
+    let size = write_payload(
+        payload,
+        path.clone(),
+        &mut signature,
+        settings.import.max_takeout_bytes,
+    )
+    .await?;
+
+    let mut connection = db
+        .acquire()
+        .await
+        .context("Failed to acquire a database connection")?;
+
+    signature
+        .verify()?
+        .claim(&mut connection, &device, &settings)
+        .await?;
+    drop(connection);
+
+    let job = ImportJob::create(
+        &db,
+        id,
+        device.id,
+        &path.to_string_lossy(),
+        true,
+        i64::try_from(size).context("Failed to convert the file size")?,
+    )
+    .await
+    .context("Failed to create import job")?;
+
+    file.keep();
+
+    let response = HttpResponse::Accepted()
+        .insert_header((
+            header::LOCATION,
+            format!("/api/v1/devices/{api_key}/imports/{}", job.id),
+        ))
+        .json(&job);
+
+    import::spawn_import_job(db.get_ref().clone(), job);
+
+    Ok(response)
+}
+
+#[get("/api/v1/devices/{api_key}/imports/{id}")]
+#[tracing::instrument(name = "Get import job", skip(db, path))]
+pub async fn get_import_job(
+    db: Data<PgPool>,
+    path: Path<(String, Uuid)>,
+) -> Result<impl Responder, ApiError> {
+    let (api_key, id) = path.into_inner();
+
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let job = ImportJob::find_by_id(&**db, device.id, id)
+        .await
+        .context("Failed to retrieve the import job")?
+        .ok_or(ApiError::UnknownImportJobId)?;
+
+    Ok(HttpResponse::Ok().json(job))
+}
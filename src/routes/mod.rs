@@ -1,3 +1,12 @@
 pub mod api;
+pub mod export;
 pub mod frontend_config;
+pub mod geofences;
 pub mod health_check;
+pub mod history;
+pub mod import;
+pub mod osmand;
+pub mod owntracks;
+pub mod places;
+pub mod stream;
+pub mod websocket;
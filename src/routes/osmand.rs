@@ -0,0 +1,107 @@
+use actix_web::{
+    HttpResponse, Responder, route,
+    web::{Data, Query},
+};
+use anyhow::Context;
+use bigdecimal::BigDecimal;
+use serde::Deserialize;
+use sqlx::PgPool;
+use time::{OffsetDateTime, format_description::well_known::Iso8601};
+
+use crate::models::{CreateReportRequest, Device};
+use crate::routes::api::{ApiError, submit_report, validate_report_request};
+use crate::settings::Settings;
+
+/// The number of meters per second in one knot, the unit of speed used by the protocol.
+const METERS_PER_SECOND_PER_KNOT: f64 = 1852.0 / 3600.0;
+
+#[derive(Deserialize, Debug)]
+pub struct OsmAndParameters {
+    id: String,
+    #[serde(alias = "latitude")]
+    lat: BigDecimal,
+    #[serde(alias = "longitude")]
+    lon: BigDecimal,
+    timestamp: Option<String>,
+    #[serde(alias = "alt")]
+    altitude: Option<BigDecimal>,
+    speed: Option<f64>,
+    #[serde(alias = "heading")]
+    bearing: Option<BigDecimal>,
+    accuracy: Option<BigDecimal>,
+}
+
+/// Parses a timestamp given either as seconds or milliseconds since the Unix epoch or as an
+/// ISO8601 formatted string.
+fn parse_timestamp(timestamp: &str) -> Option<OffsetDateTime> {
+    if let Ok(timestamp) = timestamp.parse::<i64>() {
+        // Some clients send milliseconds rather than seconds.
+        if timestamp.abs() > 100_000_000_000 {
+            OffsetDateTime::from_unix_timestamp_nanos(i128::from(timestamp) * 1_000_000).ok()
+        } else {
+            OffsetDateTime::from_unix_timestamp(timestamp).ok()
+        }
+    } else {
+        OffsetDateTime::parse(timestamp, &Iso8601::DEFAULT).ok()
+    }
+}
+
+#[route("/api/v1/osmand", method = "GET", method = "POST")]
+#[tracing::instrument(name = "Post OsmAnd report", skip(db, settings, parameters))]
+pub async fn post_osmand_report(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    parameters: Query<OsmAndParameters>,
+) -> Result<impl Responder, ApiError> {
+    let parameters = parameters.into_inner();
+
+    let device = Device::find_by_api_key(&db, &parameters.id)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    if !device.osmand_enabled {
+        return Err(ApiError::UnsignedReportsDisabled);
+    }
+
+    let timestamp = match parameters.timestamp {
+        Some(timestamp) => parse_timestamp(&timestamp).ok_or_else(|| {
+            ApiError::InvalidRequest(format!("Failed to parse timestamp: {timestamp}"))
+        })?,
+        None => OffsetDateTime::now_utc(),
+    };
+
+    let speed = parameters
+        .speed
+        .map(|speed| speed * METERS_PER_SECOND_PER_KNOT)
+        .unwrap_or_default();
+
+    let report_request = CreateReportRequest {
+        id: None,
+        timestamp,
+        latitude: parameters.lat,
+        longitude: parameters.lon,
+        altitude: parameters.altitude.unwrap_or_default(),
+        speed: BigDecimal::try_from(speed)
+            .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse speed: {e}")))?,
+        bearing: parameters.bearing.unwrap_or_default(),
+        accuracy: parameters.accuracy.unwrap_or_default(),
+        nonce: None,
+    };
+
+    validate_report_request(&report_request)?;
+
+    let mut transaction = db
+        .begin()
+        .await
+        .context("Failed to begin a transaction for the report")?;
+
+    submit_report(&mut transaction, &device, &report_request, None, &settings).await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit the report")?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use actix_web::{
+    HttpRequest, HttpResponse, Responder,
+    http::header,
+    post,
+    web::{Bytes, Data},
+};
+use anyhow::Context;
+use base64::{Engine, prelude::BASE64_STANDARD};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+use time::OffsetDateTime;
+
+use crate::models::{CreateReportRequest, Device, Report};
+use crate::routes::api::{ApiError, get_header, submit_report, validate_report_request};
+use crate::settings::Settings;
+
+/// The number of meters per second in one kilometer per hour, the unit of speed used by the app.
+const METERS_PER_SECOND_PER_KPH: f64 = 1000.0 / 3600.0;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "_type")]
+pub enum OwnTracksMessage {
+    #[serde(rename = "location")]
+    Location(OwnTracksLocation),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OwnTracksLocation {
+    tst: i64,
+    lat: f64,
+    lon: f64,
+    alt: Option<f64>,
+    vel: Option<f64>,
+    cog: Option<f64>,
+    acc: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "_type", rename_all = "lowercase")]
+enum OwnTracksResponse {
+    Card {
+        name: String,
+        tid: String,
+        topic: String,
+    },
+    Location {
+        tid: String,
+        topic: String,
+        tst: i64,
+        lat: f64,
+        lon: f64,
+        alt: i64,
+        vel: i64,
+        cog: i64,
+        acc: i64,
+    },
+}
+
+fn decimal(value: f64) -> Result<BigDecimal, ApiError> {
+    BigDecimal::from_str(&value.to_string())
+        .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse number: {e}")))
+}
+
+/// Converts a report value to the integer form expected by the app.
+#[expect(clippy::cast_possible_truncation)]
+fn integer(value: f64) -> i64 {
+    value.round() as i64
+}
+
+/// Derives a label identifying a device to the app. The API key of a device grants access to its
+/// history, so a truncated hash of its ID is used instead.
+fn device_label(device: &Device) -> String {
+    hex::encode(Sha256::digest(device.id.as_bytes()))[..12].to_string()
+}
+
+/// Derives the two character tracker ID displayed by the app from the label of a device.
+fn tracker_id(label: &str) -> String {
+    label.chars().take(2).collect::<String>().to_uppercase()
+}
+
+/// Authenticates a request using HTTP Basic authentication, where the username is the API key of
+/// the device and the password is its secret key.
+async fn authenticate(db: &PgPool, request: &HttpRequest) -> Result<Device, ApiError> {
+    let credentials = get_header(request, header::AUTHORIZATION.as_str())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|value| BASE64_STANDARD.decode(value.trim()).ok())
+        .and_then(|value| String::from_utf8(value).ok())
+        .ok_or(ApiError::InvalidCredentials)?;
+
+    let (api_key, api_secret) = credentials
+        .split_once(':')
+        .ok_or(ApiError::InvalidCredentials)?;
+
+    let device = Device::find_by_api_key(db, api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::InvalidCredentials)?;
+
+    if bool::from(
+        device
+            .api_secret
+            .expose_secret()
+            .as_bytes()
+            .ct_eq(api_secret.as_bytes()),
+    ) {
+        Ok(device)
+    } else {
+        Err(ApiError::InvalidCredentials)
+    }
+}
+
+#[post("/api/v1/owntracks")]
+#[tracing::instrument(name = "Post OwnTracks message", skip(db, settings, request, body))]
+pub async fn post_owntracks_message(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    body: Bytes,
+) -> Result<impl Responder, ApiError> {
+    let device = authenticate(&db, &request).await?;
+
+    let message: OwnTracksMessage = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse message: {e}")))?;
+
+    if let OwnTracksMessage::Location(location) = message {
+        // OwnTracks reports unknown values as negative numbers.
+        let report_request = CreateReportRequest {
+            id: None,
+            timestamp: OffsetDateTime::from_unix_timestamp(location.tst)
+                .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse timestamp: {e}")))?,
+            latitude: decimal(location.lat)?,
+            longitude: decimal(location.lon)?,
+            altitude: decimal(location.alt.unwrap_or_default())?,
+            speed: decimal(
+                location.vel.filter(|vel| *vel >= 0.0).unwrap_or_default()
+                    * METERS_PER_SECOND_PER_KPH,
+            )?,
+            bearing: decimal(location.cog.filter(|cog| *cog >= 0.0).unwrap_or_default())?,
+            accuracy: decimal(location.acc.filter(|acc| *acc >= 0.0).unwrap_or_default())?,
+            nonce: None,
+        };
+
+        validate_report_request(&report_request)?;
+
+        let mut transaction = db
+            .begin()
+            .await
+            .context("Failed to begin a transaction for the report")?;
+
+        submit_report(&mut transaction, &device, &report_request, None, &settings).await?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit the report")?;
+    }
+
+    let friends = device
+        .find_friends(&db)
+        .await
+        .context("Failed to retrieve the friends of the device")?;
+
+    let mut response = Vec::with_capacity(friends.len() * 2);
+
+    for friend in friends {
+        let Some(report) = Report::find_latest(&db, friend.id)
+            .await
+            .context("Failed to retrieve the latest report for a friend of the device")?
+        else {
+            continue;
+        };
+
+        let label = device_label(&friend);
+        let tid = tracker_id(&label);
+        let topic = format!("owntracks/follow/{label}");
+
+        response.push(OwnTracksResponse::Card {
+            name: label,
+            tid: tid.clone(),
+            topic: topic.clone(),
+        });
+
+        response.push(OwnTracksResponse::Location {
+            tid,
+            topic,
+            tst: report.timestamp.unix_timestamp(),
+            lat: report.latitude.to_f64().unwrap_or_default(),
+            lon: report.longitude.to_f64().unwrap_or_default(),
+            alt: integer(report.altitude.to_f64().unwrap_or_default()),
+            vel: integer(report.speed.to_f64().unwrap_or_default() / METERS_PER_SECOND_PER_KPH),
+            cog: integer(report.bearing.to_f64().unwrap_or_default()),
+            acc: integer(report.accuracy.to_f64().unwrap_or_default()),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -0,0 +1,181 @@
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, delete, get,
+    http::StatusCode,
+    post,
+    web::{Bytes, Data, Path, Query},
+};
+use anyhow::Context;
+use futures::TryStreamExt;
+use serde::Deserialize;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::history::stays::StayThresholds;
+use crate::history::visits::{Visit, VisitDetector, time_spent};
+use crate::models::{CreatePlaceRequest, Device, Place, Report};
+use crate::routes::api::{ApiError, claim_request_signature, time_range};
+use crate::settings::Settings;
+
+#[post("/api/v1/devices/{api_key}/places")]
+#[tracing::instrument(
+    name = "Post place to device",
+    skip(db, settings, request, api_key, body)
+)]
+pub async fn post_place(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    api_key: Path<String>,
+    body: Bytes,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    claim_request_signature(&db, &request, &body, &device, &settings).await?;
+
+    let place_request: CreatePlaceRequest = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::InvalidRequest(format!("Failed to parse place: {e}")))?;
+
+    place_request
+        .validate()
+        .map_err(|e| ApiError::InvalidRequest(e.to_string()))?;
+
+    let place = Place::create(&db, device.id, &place_request)
+        .await
+        .context("Failed to insert place")?
+        .ok_or(ApiError::PlaceNameConflict)?;
+
+    Ok(HttpResponse::build(StatusCode::CREATED)
+        .insert_header((
+            "Location",
+            format!("/api/v1/devices/{api_key}/places/{}", place.id),
+        ))
+        .json(place))
+}
+
+#[get("/api/v1/devices/{api_key}/places")]
+#[tracing::instrument(name = "Get places", skip(db, api_key))]
+pub async fn get_places(
+    db: Data<PgPool>,
+    api_key: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let places = Place::find_by_device(&db, device.id)
+        .await
+        .context("Failed to retrieve places for the device associated with the provided API key")?;
+
+    Ok(HttpResponse::Ok().json(places))
+}
+
+#[delete("/api/v1/devices/{api_key}/places/{id}")]
+#[tracing::instrument(name = "Delete place", skip(db, settings, request, path))]
+pub async fn delete_place(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    request: HttpRequest,
+    path: Path<(String, Uuid)>,
+) -> Result<impl Responder, ApiError> {
+    let (api_key, id) = path.into_inner();
+
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    claim_request_signature(&db, &request, &[], &device, &settings).await?;
+
+    if !Place::delete(&db, device.id, id)
+        .await
+        .context("Failed to delete place")?
+    {
+        return Err(ApiError::UnknownPlaceId);
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[derive(Deserialize, Debug)]
+pub struct VisitParameters {
+    #[serde(default, with = "time::serde::iso8601::option")]
+    since: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::iso8601::option")]
+    until: Option<OffsetDateTime>,
+}
+
+/// Returns the places of the device associated with the API key, along with its visits to them in
+/// ascending order of arrival.
+async fn visits(
+    db: &PgPool,
+    settings: &Settings,
+    api_key: &str,
+    parameters: &VisitParameters,
+) -> Result<(Vec<Place>, Vec<Visit>), ApiError> {
+    let device = Device::find_by_api_key(db, api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    let places = Place::find_by_device(db, device.id)
+        .await
+        .context("Failed to retrieve places for the device associated with the provided API key")?;
+
+    // Visits are detected from every report within the range, so the range must be bounded.
+    let (since, until) = time_range(parameters.since, parameters.until);
+
+    if parameters.since.is_none() || until - since > Duration::days(settings.places.max_days.into())
+    {
+        return Err(ApiError::InvalidRequest(format!(
+            "The since parameter is required, and the range may include at most {} days",
+            settings.places.max_days
+        )));
+    }
+
+    let mut detector = VisitDetector::new(&places, StayThresholds::from(&settings.stays));
+    let mut reports = Report::stream(db, device.id, since, until, None);
+
+    while let Some(report) = reports
+        .try_next()
+        .await
+        .context("Failed to fetch reports for the device associated with the provided API key")?
+    {
+        detector.push(&report);
+    }
+
+    let visits = detector.finish();
+
+    Ok((places, visits))
+}
+
+#[get("/api/v1/devices/{api_key}/places/visits")]
+#[tracing::instrument(name = "Get place visits", skip(db, settings, api_key))]
+pub async fn get_place_visits(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    api_key: Path<String>,
+    parameters: Query<VisitParameters>,
+) -> Result<impl Responder, ApiError> {
+    let (_, visits) = visits(&db, &settings, &api_key, &parameters).await?;
+
+    Ok(HttpResponse::Ok().json(visits))
+}
+
+#[get("/api/v1/devices/{api_key}/places/time")]
+#[tracing::instrument(name = "Get time spent at places", skip(db, settings, api_key))]
+pub async fn get_place_time(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    api_key: Path<String>,
+    parameters: Query<VisitParameters>,
+) -> Result<impl Responder, ApiError> {
+    let (places, visits) = visits(&db, &settings, &api_key, &parameters).await?;
+
+    Ok(HttpResponse::Ok().json(time_spent(&places, &visits)))
+}
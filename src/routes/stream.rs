@@ -0,0 +1,182 @@
+use std::fmt::Write;
+
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, get,
+    http::header::{CacheControl, CacheDirective},
+    web::{Bytes, Data, Path},
+};
+use anyhow::Context;
+use futures::stream;
+use sqlx::PgPool;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+use uuid::Uuid;
+
+use crate::live::{Broadcaster, LiveEvent};
+use crate::models::{Device, Report};
+use crate::routes::api::{ApiError, get_header};
+use crate::settings::Settings;
+
+/// The number of reports fetched from the database for each chunk of a replay.
+const REPLAY_PAGE_SIZE: i64 = 1000;
+
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+struct ReportStream {
+    db: PgPool,
+    device_id: Uuid,
+    receiver: Receiver<LiveEvent>,
+    keepalive: std::time::Duration,
+    /// The highest sequence number of any report sent, or known to have been submitted before the
+    /// stream was opened. Reports arriving from the broadcaster at or below it have already been
+    /// sent.
+    sequence: i64,
+    /// Whether reports after the sequence number are still to be replayed from the database.
+    replaying: bool,
+    finished: bool,
+}
+
+impl ReportStream {
+    fn write_event(&mut self, chunk: &mut String, report: &Report) -> Result<(), ApiError> {
+        let data = serde_json::to_string(report).context("Failed to serialize report")?;
+
+        // Writing to a string cannot fail.
+        write!(chunk, "id: {}\nevent: report\ndata: {data}\n\n", report.id).ok();
+
+        self.sequence = self.sequence.max(report.sequence);
+
+        Ok(())
+    }
+
+    /// Waits for the next chunk of events, returning `None` once no more reports can be received.
+    async fn next_chunk(&mut self) -> Result<Option<Bytes>, ApiError> {
+        loop {
+            if self.replaying {
+                let reports = Report::find_changed(
+                    &self.db,
+                    self.device_id,
+                    self.sequence,
+                    None,
+                    REPLAY_PAGE_SIZE,
+                )
+                .await
+                .context("Failed to fetch reports to replay")?;
+
+                self.replaying =
+                    reports.len() >= usize::try_from(REPLAY_PAGE_SIZE).unwrap_or(usize::MAX);
+
+                if reports.is_empty() {
+                    continue;
+                }
+
+                let mut chunk = String::new();
+
+                for report in &reports {
+                    self.write_event(&mut chunk, report)?;
+                }
+
+                return Ok(Some(Bytes::from(chunk)));
+            }
+
+            let Ok(received) =
+                actix_web::rt::time::timeout(self.keepalive, self.receiver.recv()).await
+            else {
+                return Ok(Some(Bytes::from_static(b": keepalive\n\n")));
+            };
+
+            match received {
+                Ok(LiveEvent::Report(report)) => {
+                    if report.device_id != self.device_id || report.sequence <= self.sequence {
+                        continue;
+                    }
+
+                    let mut chunk = String::new();
+                    self.write_event(&mut chunk, &report)?;
+
+                    return Ok(Some(Bytes::from(chunk)));
+                }
+                // Missed reports are recovered from the database.
+                Ok(LiveEvent::Interrupted) | Err(RecvError::Lagged(_)) => self.replaying = true,
+                Err(RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+}
+
+#[get("/api/v1/devices/{api_key}/reports/stream")]
+#[tracing::instrument(
+    name = "Stream reports",
+    skip(db, settings, broadcaster, request, api_key)
+)]
+pub async fn get_report_stream(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    broadcaster: Data<Broadcaster>,
+    request: HttpRequest,
+    api_key: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let device = Device::find_by_api_key(&db, &api_key)
+        .await
+        .context("Failed to retrieve the device associated with the provided API key")?
+        .ok_or(ApiError::UnknownApiKey)?;
+
+    // Subscribe before looking for missed reports, so that none are lost in between.
+    let receiver = broadcaster.subscribe();
+
+    let last_event_id = get_header(&request, LAST_EVENT_ID_HEADER)
+        .map(|id| {
+            Uuid::parse_str(id).map_err(|e| {
+                ApiError::InvalidRequest(format!("Failed to parse {LAST_EVENT_ID_HEADER}: {e}"))
+            })
+        })
+        .transpose()?;
+
+    // Reports submitted after the last received report are replayed. Otherwise, or if the report
+    // is unknown, only new reports are sent.
+    let last_report = match last_event_id {
+        Some(id) => Report::find_by_id(&db, device.id, &id)
+            .await
+            .context("Failed to retrieve the last received report")?,
+        None => None,
+    };
+
+    let sequence = match &last_report {
+        Some(report) => report.sequence,
+        None => device
+            .last_report_sequence(&db)
+            .await
+            .context("Failed to retrieve the report sequence of the device")?,
+    };
+
+    let report_stream = ReportStream {
+        db: db.get_ref().clone(),
+        device_id: device.id,
+        receiver,
+        keepalive: settings.live.keepalive().unsigned_abs(),
+        sequence,
+        replaying: last_report.is_some(),
+        finished: false,
+    };
+
+    let connected = stream::once(async { Ok(Bytes::from_static(b": connected\n\n")) });
+
+    let events = stream::unfold(report_stream, |mut report_stream| async move {
+        if report_stream.finished {
+            return None;
+        }
+
+        let chunk = report_stream.next_chunk().await.transpose()?;
+
+        if chunk.is_err() {
+            report_stream.finished = true;
+        }
+
+        Some((chunk, report_stream))
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        // Prevent reverse proxies from buffering the events.
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(futures::StreamExt::chain(connected, events)))
+}
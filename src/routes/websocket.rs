@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+
+use actix_web::{
+    HttpRequest, HttpResponse, get,
+    rt::time::{Instant, interval_at, timeout},
+    web::{Data, Payload},
+};
+use actix_ws::{AggregatedMessage, AggregatedMessageStream, Session};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+use uuid::Uuid;
+
+use crate::live::{Broadcaster, LiveEvent};
+use crate::models::{Device, Report};
+use crate::routes::api::ApiError;
+use crate::settings::Settings;
+
+/// The maximum size of a message accepted from a client.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// The maximum number of devices a single connection may follow.
+const MAX_SUBSCRIPTIONS: usize = 100;
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ClientMessage {
+    Subscribe { api_keys: Vec<String> },
+    Unsubscribe { api_keys: Vec<String> },
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ServerMessage<'a> {
+    Subscribed {
+        api_key: &'a str,
+        latest: Option<&'a Report>,
+    },
+    Unsubscribed {
+        api_key: &'a str,
+    },
+    Report {
+        api_key: &'a str,
+        report: &'a Report,
+    },
+    Heartbeat {
+        devices: Vec<DeviceStatus<'a>>,
+    },
+    Error {
+        api_key: Option<&'a str>,
+        reason: String,
+    },
+}
+
+#[derive(Serialize)]
+struct DeviceStatus<'a> {
+    api_key: &'a str,
+    #[serde(with = "time::serde::iso8601::option")]
+    last_report: Option<OffsetDateTime>,
+    stale: bool,
+}
+
+struct Subscription {
+    api_key: String,
+    /// The timestamp of the most recent report sent for the device.
+    last_report: Option<OffsetDateTime>,
+}
+
+impl Subscription {
+    fn update(&mut self, report: &Report) -> bool {
+        if self
+            .last_report
+            .is_some_and(|last| last >= report.timestamp)
+        {
+            return false;
+        }
+
+        self.last_report = Some(report.timestamp);
+
+        true
+    }
+}
+
+struct Sender {
+    session: Session,
+    timeout: std::time::Duration,
+}
+
+impl Sender {
+    /// Sends a message, failing if the client does not accept it in time.
+    async fn send(&mut self, message: &ServerMessage<'_>) -> anyhow::Result<()> {
+        let text = serde_json::to_string(message).context("Failed to serialize message")?;
+
+        timeout(self.timeout, self.session.text(text))
+            .await
+            .context("Timed out sending a message to the client")?
+            .context("The session was closed")
+    }
+}
+
+/// A WebSocket connection following the reports of any number of devices.
+struct Follower {
+    db: PgPool,
+    sender: Sender,
+    subscriptions: HashMap<Uuid, Subscription>,
+    stale_after: Duration,
+}
+
+impl Follower {
+    async fn subscribe(&mut self, api_keys: &[String]) -> anyhow::Result<()> {
+        for api_key in api_keys {
+            let Some(device) = Device::find_by_api_key(&self.db, api_key)
+                .await
+                .context("Failed to retrieve the device associated with the provided API key")?
+            else {
+                self.sender
+                    .send(&ServerMessage::Error {
+                        api_key: Some(api_key),
+                        reason: ApiError::UnknownApiKey.to_string(),
+                    })
+                    .await?;
+                continue;
+            };
+
+            if !self.subscriptions.contains_key(&device.id)
+                && self.subscriptions.len() >= MAX_SUBSCRIPTIONS
+            {
+                self.sender
+                    .send(&ServerMessage::Error {
+                        api_key: Some(api_key),
+                        reason: format!("At most {MAX_SUBSCRIPTIONS} devices may be followed"),
+                    })
+                    .await?;
+                continue;
+            }
+
+            let latest = Report::find_latest(&self.db, device.id)
+                .await
+                .context("Failed to retrieve the latest report for the device")?;
+
+            self.subscriptions.insert(
+                device.id,
+                Subscription {
+                    api_key: api_key.clone(),
+                    last_report: latest.as_ref().map(|report| report.timestamp),
+                },
+            );
+
+            self.sender
+                .send(&ServerMessage::Subscribed {
+                    api_key,
+                    latest: latest.as_ref(),
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, api_keys: &[String]) -> anyhow::Result<()> {
+        for api_key in api_keys {
+            self.subscriptions
+                .retain(|_, subscription| subscription.api_key != *api_key);
+
+            self.sender
+                .send(&ServerMessage::Unsubscribed { api_key })
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn send_report(&mut self, report: &Report) -> anyhow::Result<()> {
+        let Some(subscription) = self.subscriptions.get_mut(&report.device_id) else {
+            return Ok(());
+        };
+
+        if subscription.update(report) {
+            self.sender
+                .send(&ServerMessage::Report {
+                    api_key: &subscription.api_key,
+                    report,
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the latest report of each device after reports may have been missed. Intermediate
+    /// reports are skipped, as only the current location of each device is of interest.
+    async fn resync(&mut self) -> anyhow::Result<()> {
+        let device_ids: Vec<Uuid> = self.subscriptions.keys().copied().collect();
+
+        for device_id in device_ids {
+            let latest = Report::find_latest(&self.db, device_id)
+                .await
+                .context("Failed to retrieve the latest report for the device")?;
+
+            if let Some(report) = latest {
+                self.send_report(&report).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send_heartbeat(&mut self) -> anyhow::Result<()> {
+        let stale_before = OffsetDateTime::now_utc() - self.stale_after;
+
+        let devices = self
+            .subscriptions
+            .values()
+            .map(|subscription| DeviceStatus {
+                api_key: &subscription.api_key,
+                last_report: subscription.last_report,
+                stale: subscription
+                    .last_report
+                    .is_none_or(|last_report| last_report < stale_before),
+            })
+            .collect();
+
+        self.sender
+            .send(&ServerMessage::Heartbeat { devices })
+            .await
+    }
+
+    async fn handle_message(
+        &mut self,
+        message: AggregatedMessage,
+    ) -> anyhow::Result<ControlFlow<()>> {
+        match message {
+            AggregatedMessage::Text(text) => match serde_json::from_str(&text) {
+                Ok(ClientMessage::Subscribe { api_keys }) => self.subscribe(&api_keys).await?,
+                Ok(ClientMessage::Unsubscribe { api_keys }) => self.unsubscribe(&api_keys).await?,
+                Err(e) => {
+                    self.sender
+                        .send(&ServerMessage::Error {
+                            api_key: None,
+                            reason: format!("Failed to parse message: {e}"),
+                        })
+                        .await?;
+                }
+            },
+            AggregatedMessage::Binary(_) => {
+                self.sender
+                    .send(&ServerMessage::Error {
+                        api_key: None,
+                        reason: "Binary messages are not supported".to_string(),
+                    })
+                    .await?;
+            }
+            AggregatedMessage::Ping(bytes) => {
+                self.sender
+                    .session
+                    .pong(&bytes)
+                    .await
+                    .context("The session was closed")?;
+            }
+            AggregatedMessage::Pong(_) => {}
+            AggregatedMessage::Close(_) => return Ok(ControlFlow::Break(())),
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+
+    async fn run(
+        &mut self,
+        mut messages: AggregatedMessageStream,
+        mut receiver: Receiver<LiveEvent>,
+        heartbeat: std::time::Duration,
+    ) -> anyhow::Result<()> {
+        let mut heartbeat = interval_at(Instant::now() + heartbeat, heartbeat);
+
+        loop {
+            tokio::select! {
+                message = messages.recv() => match message {
+                    Some(message) => {
+                        let message = message.context("Failed to receive a message")?;
+
+                        if self.handle_message(message).await?.is_break() {
+                            return Ok(());
+                        }
+                    }
+                    None => return Ok(()),
+                },
+                event = receiver.recv() => match event {
+                    Ok(LiveEvent::Report(report)) => self.send_report(&report).await?,
+                    // Reports are missed if the client is too slow to keep up.
+                    Ok(LiveEvent::Interrupted) | Err(RecvError::Lagged(_)) => self.resync().await?,
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                _ = heartbeat.tick() => self.send_heartbeat().await?,
+            }
+        }
+    }
+}
+
+#[get("/api/v1/live")]
+#[tracing::instrument(
+    name = "Follow devices",
+    skip(db, settings, broadcaster, request, body)
+)]
+pub async fn get_live_websocket(
+    db: Data<PgPool>,
+    settings: Data<Settings>,
+    broadcaster: Data<Broadcaster>,
+    request: HttpRequest,
+    body: Payload,
+) -> Result<HttpResponse, ApiError> {
+    let (response, session, messages) = actix_ws::handle(&request, body)
+        .map_err(|e| ApiError::InvalidRequest(format!("Failed to open WebSocket: {e}")))?;
+
+    let messages = messages
+        .max_frame_size(MAX_MESSAGE_SIZE)
+        .aggregate_continuations()
+        .max_continuation_size(MAX_MESSAGE_SIZE);
+
+    let receiver = broadcaster.subscribe();
+    let heartbeat = settings.live.heartbeat().unsigned_abs();
+
+    // A client that cannot accept a message before the next heartbeat is disconnected.
+    let mut follower = Follower {
+        db: db.get_ref().clone(),
+        sender: Sender {
+            session,
+            timeout: heartbeat,
+        },
+        subscriptions: HashMap::new(),
+        stale_after: settings.live.stale_after(),
+    };
+
+    actix_web::rt::spawn(async move {
+        if let Err(e) = follower.run(messages, receiver, heartbeat).await {
+            tracing::warn!("Closing WebSocket: {e:#}");
+        }
+
+        timeout(heartbeat, follower.sender.session.close(None))
+            .await
+            .ok();
+    });
+
+    Ok(response)
+}
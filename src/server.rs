@@ -19,6 +19,9 @@
 };
 use tracing_actix_web::TracingLogger;
 
+use crate::import::spawn_import_job;
+use crate::live::Broadcaster;
+use crate::models::ImportJob;
 use crate::settings::{DatabaseSettings, Settings};
 
 pub struct Application {
@@ -36,6 +39,16 @@ pub async fn build(settings: Settings) -> std::io::Result<Self> {
             return Err(std::io::Error::other("Failed to migrate database"));
         }
 
+        match ImportJob::find_unfinished(&db_pool, None, None).await {
+            Ok(jobs) => {
+                for job in jobs {
+                    tracing::info!("Resuming import job {}", job.id);
+                    spawn_import_job(db_pool.clone(), job);
+                }
+            }
+            Err(e) => tracing::error!("Failed to retrieve unfinished import jobs: {e}"),
+        }
+
         let address = format!(
             "{}:{}",
             settings.application.address, settings.application.port
@@ -45,7 +58,10 @@ pub async fn build(settings: Settings) -> std::io::Result<Self> {
         tracing::info!("Listening on {}", &address);
         let port = listener.local_addr()?.port();
 
-        let server = run(listener, db_pool, settings)?;
+        let broadcaster = Broadcaster::new(settings.live.channel_capacity.get());
+        broadcaster.spawn_listener(db_pool.clone());
+
+        let server = run(listener, db_pool, broadcaster, settings)?;
 
         Ok(Self { port, server })
     }
@@ -73,8 +89,14 @@ pub fn get_db_pool(settings: &DatabaseSettings) -> std::io::Result<PgPool> {
         .connect_lazy_with(options))
 }
 
-fn run(listener: TcpListener, db_pool: PgPool, settings: Settings) -> std::io::Result<Server> {
+fn run(
+    listener: TcpListener,
+    db_pool: PgPool,
+    broadcaster: Broadcaster,
+    settings: Settings,
+) -> std::io::Result<Server> {
     let db_pool = Data::new(db_pool);
+    let broadcaster = Data::new(broadcaster);
     let settings = Data::new(settings);
 
     let server = HttpServer::new(move || {
@@ -128,12 +150,41 @@ fn run(listener: TcpListener, db_pool: PgPool, settings: Settings) -> std::io::R
             .app_data(query_cfg)
             .app_data(db_pool.clone())
             .app_data(settings.clone())
+            .app_data(broadcaster.clone())
             .service(crate::routes::health_check::health_check)
             .service(crate::routes::frontend_config::get_frontend_config)
+            .service(crate::routes::api::get_latest_report)
             .service(crate::routes::api::get_report_count)
+            .service(crate::routes::history::get_proximity)
+            .service(crate::routes::history::get_colocation)
+            .service(crate::routes::history::get_trips)
+            .service(crate::routes::history::get_statistics)
+            .service(crate::routes::history::get_stays)
+            .service(crate::routes::history::get_summaries)
+            .service(crate::routes::places::get_places)
+            .service(crate::routes::places::get_place_visits)
+            .service(crate::routes::places::get_place_time)
+            .service(crate::routes::places::post_place)
+            .service(crate::routes::places::delete_place)
+            .service(crate::routes::geofences::get_geofences)
+            .service(crate::routes::geofences::get_geofence_events)
+            .service(crate::routes::geofences::post_geofence)
+            .service(crate::routes::geofences::delete_geofence)
+            .service(crate::routes::export::get_reports_gpx)
+            .service(crate::routes::stream::get_report_stream)
             .service(crate::routes::api::get_report_by_id)
             .service(crate::routes::api::get_reports)
             .service(crate::routes::api::post_report)
+            .service(crate::routes::api::post_report_batch)
+            .service(crate::routes::api::post_report_search)
+            .service(crate::routes::api::post_report_search_count)
+            .service(crate::routes::export::post_report_search_gpx)
+            .service(crate::routes::import::post_import)
+            .service(crate::routes::import::post_takeout_import)
+            .service(crate::routes::import::get_import_job)
+            .service(crate::routes::osmand::post_osmand_report)
+            .service(crate::routes::owntracks::post_owntracks_message)
+            .service(crate::routes::websocket::get_live_websocket)
             .service(actix_files::Files::new("/static", "./static").index_file("index.html"))
             .default_service(web::route().to(|| async {
                 NamedFile::open_async("./static/app/index.html")
@@ -1,8 +1,21 @@
+use std::num::{NonZeroU32, NonZeroUsize};
+
+use time::Duration;
+
 #[derive(serde::Deserialize, Clone)]
 pub struct Settings {
     pub application: ApplicationSettings,
     pub database: DatabaseSettings,
     pub frontend: FrontendSettings,
+    pub geofences: GeofenceSettings,
+    pub import: ImportSettings,
+    pub live: LiveSettings,
+    pub places: PlaceSettings,
+    pub reports: ReportSettings,
+    pub security: SecuritySettings,
+    pub stays: StaySettings,
+    pub summaries: SummarySettings,
+    pub trips: TripSettings,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -21,6 +34,118 @@ pub struct FrontendSettings {
     pub maps_api_key: String,
 }
 
+#[derive(serde::Deserialize, Clone)]
+pub struct GeofenceSettings {
+    /// The distance in meters beyond a geofence a device must travel to exit it, unless the
+    /// geofence specifies its own.
+    pub hysteresis_meters: u32,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct ImportSettings {
+    pub directory: String,
+    pub max_takeout_bytes: u64,
+    pub max_upload_bytes: usize,
+}
+
+/// Settings for live streams. The capacity and intervals must be nonzero, as an empty channel
+/// cannot be created and a zero interval would fire continuously.
+#[derive(serde::Deserialize, Clone)]
+pub struct LiveSettings {
+    pub channel_capacity: NonZeroUsize,
+    pub heartbeat_seconds: NonZeroU32,
+    pub keepalive_seconds: NonZeroU32,
+    pub stale_seconds: u32,
+}
+
+impl LiveSettings {
+    #[must_use]
+    pub fn heartbeat(&self) -> Duration {
+        Duration::seconds(i64::from(self.heartbeat_seconds.get()))
+    }
+
+    #[must_use]
+    pub fn keepalive(&self) -> Duration {
+        Duration::seconds(i64::from(self.keepalive_seconds.get()))
+    }
+
+    #[must_use]
+    pub fn stale_after(&self) -> Duration {
+        Duration::seconds(i64::from(self.stale_seconds))
+    }
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct ReportSettings {
+    /// The maximum number of reports within the range of a simplified track.
+    pub max_simplified_reports: usize,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct SecuritySettings {
+    pub nonce_retention_seconds: u32,
+    pub signature_clock_skew_seconds: u32,
+}
+
+impl SecuritySettings {
+    #[must_use]
+    pub fn nonce_retention(&self) -> Duration {
+        Duration::seconds(i64::from(self.nonce_retention_seconds))
+    }
+
+    #[must_use]
+    pub fn signature_clock_skew(&self) -> Duration {
+        Duration::seconds(i64::from(self.signature_clock_skew_seconds))
+    }
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct PlaceSettings {
+    /// The longest range, in days, over which visits are detected by a single request.
+    pub max_days: u32,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct StaySettings {
+    pub radius_meters: u32,
+    pub duration_seconds: u32,
+    /// Reports with a worse accuracy than this are ignored.
+    pub max_accuracy_meters: u32,
+}
+
+impl StaySettings {
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        Duration::seconds(i64::from(self.duration_seconds))
+    }
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct SummarySettings {
+    /// The IANA time zone used for day boundaries unless another is requested.
+    pub timezone: String,
+    pub max_days: u32,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct TripSettings {
+    pub gap_seconds: u32,
+    pub stationary_seconds: u32,
+    pub stationary_radius_meters: u32,
+}
+
+impl TripSettings {
+    #[must_use]
+    pub fn gap(&self) -> Duration {
+        Duration::seconds(i64::from(self.gap_seconds))
+    }
+
+    #[must_use]
+    pub fn stationary(&self) -> Duration {
+        Duration::seconds(i64::from(self.stationary_seconds))
+    }
+}
+
 #[derive(PartialEq)]
 pub enum Environment {
     Development,
@@ -0,0 +1,96 @@
+use anyhow::Context;
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+pub const SIGNATURE_TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+pub const SIGNATURE_VERSION_HEADER: &str = "X-Signature-Version";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignatureVersion {
+    /// Signs the reformatted fields of an individual report.
+    V1,
+    /// Signs the request method, path, timestamp header and raw body.
+    V2,
+}
+
+impl TryFrom<&str> for SignatureVersion {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.trim() {
+            "1" => Ok(Self::V1),
+            "2" => Ok(Self::V2),
+            other => Err(format!(
+                "{other} is not a supported signature version. Use either `1` or `2`",
+            )),
+        }
+    }
+}
+
+/// Incrementally calculates the version 2 signature of a request, allowing large bodies to be
+/// signed or verified as they are streamed.
+pub struct RequestSigner {
+    mac: HmacSha256,
+}
+
+impl RequestSigner {
+    pub fn new(secret: &str, method: &str, path: &str, timestamp: &str) -> anyhow::Result<Self> {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+            .context("Failed to create HMAC instance for signature generation")?;
+
+        mac.update(format!("{method}\n{path}\n{timestamp}\n").as_bytes());
+
+        Ok(Self { mac })
+    }
+
+    pub fn update(&mut self, body: &[u8]) {
+        self.mac.update(body);
+    }
+
+    #[must_use]
+    pub fn finalize(self) -> String {
+        hex::encode(self.mac.finalize().into_bytes())
+    }
+
+    /// Checks the signature in constant time.
+    #[must_use]
+    pub fn verify(self, signature: &str) -> bool {
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+
+        self.mac.verify_slice(&signature).is_ok()
+    }
+}
+
+/// Calculates the version 2 signature of a request.
+pub fn sign_request(
+    secret: &str,
+    method: &str,
+    path: &str,
+    timestamp: &str,
+    body: &[u8],
+) -> anyhow::Result<String> {
+    let mut signer = RequestSigner::new(secret, method, path, timestamp)?;
+    signer.update(body);
+
+    Ok(signer.finalize())
+}
+
+/// Checks a version 2 signature of a request in constant time.
+pub fn verify_request(
+    secret: &str,
+    method: &str,
+    path: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+) -> anyhow::Result<bool> {
+    let mut signer = RequestSigner::new(secret, method, path, timestamp)?;
+    signer.update(body);
+
+    Ok(signer.verify(signature))
+}
@@ -0,0 +1,111 @@
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn get_colocation(
+    server: &TestApplication,
+    api_key: &str,
+    other_api_key: &str,
+    query: &str,
+) -> reqwest::Response {
+    reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/colocation/{other_api_key}?{query}",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_colocation_returns_intervals_with_interpolated_positions() {
+    let server = run_server().await;
+    let (first_api_key, first_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+    let (second_api_key, second_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // The first device stays put while the second stays within about 22 m of it before leaving.
+    server
+        .submit_reports(
+            &first_api_key,
+            &first_api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -111.5),
+                ReportRequest::at("2023-01-01T00:02:00+00:00", 40.0, -111.5),
+                ReportRequest::at("2023-01-01T00:04:00+00:00", 40.0, -111.5),
+                ReportRequest::at("2023-01-01T00:06:00+00:00", 40.0, -111.5),
+            ],
+        )
+        .await;
+
+    server
+        .submit_reports(
+            &second_api_key,
+            &second_api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:01:00+00:00", 40.0001, -111.5),
+                ReportRequest::at("2023-01-01T00:03:00+00:00", 40.0002, -111.5),
+                ReportRequest::at("2023-01-01T00:05:00+00:00", 40.1, -111.5),
+                ReportRequest::at("2023-01-01T00:07:00+00:00", 40.1, -111.5),
+            ],
+        )
+        .await;
+
+    let response = get_colocation(&server, &first_api_key, &second_api_key, "distance=100").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let intervals: Vec<serde_json::Value> =
+        response.json().await.expect("Failed to parse intervals");
+
+    assert_eq!(1, intervals.len());
+    assert_eq!("+002023-01-01T00:01:00.000000000Z", intervals[0]["start"]);
+    assert_eq!("+002023-01-01T00:03:00.000000000Z", intervals[0]["end"]);
+    assert!(
+        intervals[0]["min_distance"]
+            .as_f64()
+            .is_some_and(|distance| (11.0..12.0).contains(&distance))
+    );
+
+    // Positions are not interpolated across gaps longer than the maximum.
+    let intervals: Vec<serde_json::Value> = get_colocation(
+        &server,
+        &first_api_key,
+        &second_api_key,
+        "distance=100&max_gap=60",
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse intervals");
+
+    assert!(intervals.is_empty());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_colocation_returns_errors_for_invalid_requests() {
+    let server = run_server().await;
+    let (first_api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+    let (second_api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    for query in ["", "distance=0", "distance=100&max_gap=0"] {
+        let response = get_colocation(&server, &first_api_key, &second_api_key, query).await;
+
+        assert_eq!(400, response.status().as_u16(), "{query}");
+    }
+
+    let response = get_colocation(&server, &first_api_key, "unknown", "distance=100").await;
+
+    assert_eq!(404, response.status().as_u16());
+}
@@ -0,0 +1,131 @@
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn get_gpx(server: &TestApplication, api_key: &str, query: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .get(format!(
+            "{}/api/v1/devices/{api_key}/reports.gpx{query}",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_gpx_splits_segments_on_gaps() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                "2023-01-01T00:00:00+00:00",
+                "2023-01-01T00:01:00+00:00",
+                "2023-01-01T01:00:00+00:00",
+            ]
+            .map(ReportRequest::sample),
+        )
+        .await;
+
+    let response = get_gpx(&server, &api_key, "").await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        Some("application/gpx+xml"),
+        response
+            .headers()
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+    );
+    assert_eq!(
+        Some(format!("attachment; filename=\"{api_key}.gpx\"").as_str()),
+        response
+            .headers()
+            .get("Content-Disposition")
+            .and_then(|value| value.to_str().ok())
+    );
+
+    let body = response.text().await.expect("Failed to read response body");
+
+    assert!(body.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert!(body.ends_with("</trkseg>\n</trk>\n</gpx>\n"));
+    assert_eq!(2, body.matches("<trkseg>").count());
+    assert_eq!(3, body.matches("<trkpt ").count());
+    assert!(body.contains(r#"<trkpt lat="40" lon="-111.5000">"#));
+    assert!(body.contains("<ele>1500.2500</ele>"));
+    assert!(body.contains("<time>2023-01-01T00:01:00Z</time>"));
+    assert!(body.contains("<gpxtpx:speed>12.5000</gpxtpx:speed>"));
+    assert!(body.contains("<gpxtpx:course>0</gpxtpx:course>"));
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_gpx_honors_since_and_until() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                "2023-01-01T00:00:00+00:00",
+                "2023-01-01T00:01:00+00:00",
+                "2023-01-01T00:02:00+00:00",
+            ]
+            .map(ReportRequest::sample),
+        )
+        .await;
+
+    let response = get_gpx(
+        &server,
+        &api_key,
+        "?since=2023-01-01T00:00:30Z&until=2023-01-01T00:01:30Z",
+    )
+    .await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body = response.text().await.expect("Failed to read response body");
+
+    assert_eq!(1, body.matches("<trkpt ").count());
+    assert!(body.contains("<time>2023-01-01T00:01:00Z</time>"));
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_gpx_returns_empty_track_without_reports() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = get_gpx(&server, &api_key, "").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body = response.text().await.expect("Failed to read response body");
+
+    assert!(!body.contains("<trkseg>"));
+    assert!(body.ends_with("</trk>\n</gpx>\n"));
+}
+
+#[actix_web::test]
+async fn get_reports_gpx_returns_404_for_invalid_api_key() {
+    let server = run_server().await;
+
+    let response = get_gpx(&server, "invalid", "").await;
+
+    assert_eq!(404, response.status().as_u16());
+}
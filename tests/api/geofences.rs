@@ -0,0 +1,403 @@
+use serde_json::json;
+
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn create_geofence(
+    server: &TestApplication,
+    api_key: &str,
+    api_secret: &str,
+    body: &serde_json::Value,
+) -> serde_json::Value {
+    let response = server
+        .post_signed(
+            &format!("/api/v1/devices/{api_key}/geofences"),
+            api_secret,
+            &body.to_string(),
+        )
+        .await;
+
+    assert_eq!(201, response.status().as_u16());
+
+    response.json().await.expect("Failed to parse geofence")
+}
+
+#[expect(clippy::expect_used)]
+async fn get_events(
+    server: &TestApplication,
+    api_key: &str,
+    query: &str,
+) -> Vec<serde_json::Value> {
+    reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/geofences/events?{query}",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+    .json()
+    .await
+    .expect("Failed to parse geofence events")
+}
+
+fn summarize(events: &[serde_json::Value]) -> Vec<(String, String)> {
+    events
+        .iter()
+        .map(|event| {
+            (
+                event["kind"].as_str().unwrap_or_default().to_string(),
+                event["timestamp"].as_str().unwrap_or_default()[14..19].to_string(),
+            )
+        })
+        .collect()
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn circular_geofence_records_events_with_hysteresis() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let geofence = create_geofence(
+        &server,
+        &api_key,
+        &api_secret,
+        &json!({
+            "name": "Home",
+            "latitude": 40.0,
+            "longitude": -105.0,
+            "radius": 100.0,
+            "hysteresis": 50.0,
+        }),
+    )
+    .await;
+
+    assert_eq!(false, geofence["inside"]);
+
+    // About 133 meters from the center is outside of the radius but within the hysteresis, while
+    // about 222 meters is beyond it.
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:00:00+00:00", 40.01, -105.0),
+                ReportRequest::at("2023-01-01T00:01:00+00:00", 40.0, -105.0),
+                ReportRequest::at("2023-01-01T00:02:00+00:00", 40.0012, -105.0),
+                ReportRequest::at("2023-01-01T00:03:00+00:00", 40.0, -105.0),
+                ReportRequest::at("2023-01-01T00:04:00+00:00", 40.002, -105.0),
+                ReportRequest::at("2023-01-01T00:05:00+00:00", 40.0005, -105.0),
+            ],
+        )
+        .await;
+
+    let expected = vec![
+        ("enter".to_string(), "00:01".to_string()),
+        ("exit".to_string(), "00:04".to_string()),
+        ("enter".to_string(), "00:05".to_string()),
+    ];
+
+    let events = get_events(&server, &api_key, "").await;
+
+    assert_eq!(expected, summarize(&events));
+    assert_eq!(geofence["id"], events[0]["geofence_id"]);
+    assert_eq!("Home", events[0]["name"]);
+
+    // Reports submitted late do not change the state of the geofence.
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[ReportRequest::at(
+                "2023-01-01T00:04:30+00:00",
+                40.01,
+                -105.0,
+            )],
+        )
+        .await;
+
+    assert_eq!(
+        expected,
+        summarize(&get_events(&server, &api_key, "").await)
+    );
+
+    assert_eq!(
+        expected[1..],
+        summarize(&get_events(&server, &api_key, "since=2023-01-01T00:03:00Z").await)
+    );
+
+    let geofences: Vec<serde_json::Value> = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/geofences",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+    .json()
+    .await
+    .expect("Failed to parse geofences");
+
+    assert_eq!(1, geofences.len());
+    assert_eq!(true, geofences[0]["inside"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn polygon_geofence_records_events_for_osmand_reports() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .enable_osmand(&api_key)
+        .await
+        .expect("Failed to enable OsmAnd for the test device");
+
+    let geometry = json!({
+        "type": "Polygon",
+        "coordinates": [[
+            [-105.01, 39.99],
+            [-104.99, 39.99],
+            [-104.99, 40.01],
+            [-105.01, 40.01],
+            [-105.01, 39.99],
+        ]],
+    });
+
+    let geofence = create_geofence(
+        &server,
+        &api_key,
+        &api_secret,
+        &json!({"name": "Campus", "geometry": geometry, "hysteresis": 0.0}),
+    )
+    .await;
+
+    assert_eq!(geometry, geofence["geometry"]);
+    assert!(geofence["radius"].is_null());
+
+    for (timestamp, latitude) in [(1_672_531_200, 40.0), (1_672_531_260, 40.02)] {
+        let response = reqwest::Client::new()
+            .post(format!(
+                "{}/api/v1/osmand?id={api_key}&lat={latitude}&lon=-105.0&timestamp={timestamp}",
+                server.base_url
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    assert_eq!(
+        vec![
+            ("enter".to_string(), "00:00".to_string()),
+            ("exit".to_string(), "00:01".to_string()),
+        ],
+        summarize(&get_events(&server, &api_key, "").await)
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn multipolygon_geofence_applies_holes_to_their_own_polygon() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // A square with a hole, and an island within the hole.
+    let geometry = json!({
+        "type": "MultiPolygon",
+        "coordinates": [
+            [
+                [[-105.1, 39.9], [-104.9, 39.9], [-104.9, 40.1], [-105.1, 40.1], [-105.1, 39.9]],
+                [[-105.05, 39.95], [-104.95, 39.95], [-104.95, 40.05], [-105.05, 40.05], [-105.05, 39.95]],
+            ],
+            [
+                [[-105.01, 39.99], [-104.99, 39.99], [-104.99, 40.01], [-105.01, 40.01], [-105.01, 39.99]],
+            ],
+        ],
+    });
+
+    create_geofence(
+        &server,
+        &api_key,
+        &api_secret,
+        &json!({"name": "Lake", "geometry": geometry, "hysteresis": 0.0}),
+    )
+    .await;
+
+    // The island, the hole around it, the square, and then outside of everything.
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -105.0),
+                ReportRequest::at("2023-01-01T00:01:00+00:00", 40.03, -105.0),
+                ReportRequest::at("2023-01-01T00:02:00+00:00", 40.08, -105.0),
+                ReportRequest::at("2023-01-01T00:03:00+00:00", 40.2, -105.0),
+            ],
+        )
+        .await;
+
+    assert_eq!(
+        vec![
+            ("enter".to_string(), "00:00".to_string()),
+            ("exit".to_string(), "00:01".to_string()),
+            ("enter".to_string(), "00:02".to_string()),
+            ("exit".to_string(), "00:03".to_string()),
+        ],
+        summarize(&get_events(&server, &api_key, "").await)
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn polygon_geofence_may_cross_the_antimeridian() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let geometry = json!({
+        "type": "Polygon",
+        "coordinates": [[[179.0, -1.0], [-179.0, -1.0], [-179.0, 1.0], [179.0, 1.0], [179.0, -1.0]]],
+    });
+
+    create_geofence(
+        &server,
+        &api_key,
+        &api_secret,
+        &json!({"name": "Date Line", "geometry": geometry, "hysteresis": 0.0}),
+    )
+    .await;
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:00:00+00:00", 0.0, 179.5),
+                ReportRequest::at("2023-01-01T00:01:00+00:00", 0.0, -179.5),
+                ReportRequest::at("2023-01-01T00:02:00+00:00", 0.0, 0.0),
+            ],
+        )
+        .await;
+
+    assert_eq!(
+        vec![
+            ("enter".to_string(), "00:00".to_string()),
+            ("exit".to_string(), "00:02".to_string()),
+        ],
+        summarize(&get_events(&server, &api_key, "").await)
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn geofences_can_be_deleted() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let geofence = create_geofence(
+        &server,
+        &api_key,
+        &api_secret,
+        &json!({"name": "Home", "latitude": 40.0, "longitude": -105.0, "radius": 100.0}),
+    )
+    .await;
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -105.0)],
+        )
+        .await;
+
+    assert_eq!(1, get_events(&server, &api_key, "").await.len());
+
+    let path = format!(
+        "/api/v1/devices/{api_key}/geofences/{}",
+        geofence["id"].as_str().unwrap_or_default()
+    );
+
+    let response = server.delete_signed(&path, &api_secret).await;
+    assert_eq!(204, response.status().as_u16());
+
+    let response = server
+        .delete_signed(
+            &format!(
+                "/api/v1/devices/{api_key}/geofences/{}",
+                uuid::Uuid::new_v4()
+            ),
+            &api_secret,
+        )
+        .await;
+    assert_eq!(404, response.status().as_u16());
+
+    assert!(get_events(&server, &api_key, "").await.is_empty());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_geofence_rejects_invalid_geofences() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    create_geofence(
+        &server,
+        &api_key,
+        &api_secret,
+        &json!({"name": "Home", "latitude": 40.0, "longitude": -105.0, "radius": 100.0}),
+    )
+    .await;
+
+    let path = format!("/api/v1/devices/{api_key}/geofences");
+    let square = json!({
+        "type": "Polygon",
+        "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]],
+    });
+
+    for (body, status) in [
+        (
+            json!({"name": "Home", "latitude": 41.0, "longitude": -105.0, "radius": 50.0}),
+            409,
+        ),
+        (json!({"name": "Work"}), 400),
+        (
+            json!({"name": "Work", "latitude": 41.0, "longitude": -105.0}),
+            400,
+        ),
+        (
+            json!({"name": "Work", "latitude": 41.0, "longitude": -105.0, "radius": 50.0, "geometry": square}),
+            400,
+        ),
+        (
+            json!({"name": "Work", "geometry": {"type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 0.0]]]}}),
+            400,
+        ),
+        (
+            json!({"name": "Work", "latitude": 41.0, "longitude": -105.0, "radius": 50.0, "hysteresis": -1.0}),
+            400,
+        ),
+    ] {
+        let response = server
+            .post_signed(&path, &api_secret, &body.to_string())
+            .await;
+
+        assert_eq!(status, response.status().as_u16(), "{body}");
+    }
+}
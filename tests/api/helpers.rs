@@ -1,15 +1,19 @@
 use std::str::FromStr;
 
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
 use sqlx::{
-    ConnectOptions, PgPool,
+    ConnectOptions, Connection, PgConnection, PgPool,
     migrate::MigrateDatabase,
     postgres::{PgConnectOptions, PgPoolOptions},
 };
+use time::OffsetDateTime;
 use uuid::Uuid;
 
 use com_calindora_follow::server::{Application, get_db_pool};
 use com_calindora_follow::settings::{DatabaseSettings, Settings, get_settings};
 use com_calindora_follow::telemetry::{get_subscriber, init_subscriber};
+use com_calindora_follow::util::TIMESTAMP_FORMAT_SUBSECOND;
 
 static TRACING: std::sync::LazyLock<()> = std::sync::LazyLock::new(|| {
     if std::env::var("TEST_LOG").is_ok() {
@@ -23,6 +27,137 @@
     }
 });
 
+#[derive(serde::Serialize)]
+pub struct ReportRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub timestamp: String,
+    pub latitude: String,
+    pub longitude: String,
+    pub altitude: String,
+    pub speed: String,
+    pub bearing: String,
+    pub accuracy: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+impl ReportRequest {
+    pub fn new(
+        timestamp: &str,
+        latitude: f64,
+        longitude: f64,
+        altitude: f64,
+        speed: f64,
+        bearing: f64,
+        accuracy: f64,
+    ) -> Self {
+        Self {
+            id: None,
+            timestamp: timestamp.to_string(),
+            latitude: format!("{latitude:.12}"),
+            longitude: format!("{longitude:.12}"),
+            altitude: format!("{altitude:.12}"),
+            speed: format!("{speed:.12}"),
+            bearing: format!("{bearing:.12}"),
+            accuracy: format!("{accuracy:.12}"),
+            nonce: None,
+        }
+    }
+
+    /// Creates a report at the given position, stationary at sea level with an accuracy of five
+    /// meters.
+    pub fn at(timestamp: &str, latitude: f64, longitude: f64) -> Self {
+        Self::new(timestamp, latitude, longitude, 0.0, 0.0, 0.0, 5.0)
+    }
+
+    /// Creates a report with typical values in every field.
+    pub fn sample(timestamp: &str) -> Self {
+        Self::new(timestamp, 40.0, -111.5, 1500.25, 12.5, 360.0, 5.0)
+    }
+
+    #[must_use]
+    pub fn with_altitude(mut self, altitude: f64) -> Self {
+        self.altitude = format!("{altitude:.12}");
+        self
+    }
+
+    #[must_use]
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = format!("{speed:.12}");
+        self
+    }
+
+    #[must_use]
+    pub fn with_accuracy(mut self, accuracy: f64) -> Self {
+        self.accuracy = format!("{accuracy:.12}");
+        self
+    }
+
+    #[expect(clippy::unwrap_used)]
+    pub fn with_nonce(timestamp: OffsetDateTime, nonce: &str) -> Self {
+        let mut request = Self::new(
+            &timestamp.format(TIMESTAMP_FORMAT_SUBSECOND).unwrap(),
+            0.0,
+            1.0,
+            2.0,
+            3.0,
+            4.0,
+            5.0,
+        );
+
+        request.nonce = Some(nonce.to_string());
+
+        request
+    }
+
+    pub fn signature(&self, secret: &str) -> String {
+        type HmacSha256 = Hmac<Sha256>;
+
+        let input = format!(
+            "{}{}{}{}{}{}{}{}",
+            self.timestamp,
+            self.latitude,
+            self.longitude,
+            self.altitude,
+            self.speed,
+            self.bearing,
+            self.accuracy,
+            self.nonce.as_deref().unwrap_or_default()
+        );
+
+        #[expect(clippy::unwrap_used)]
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(input.as_bytes());
+
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    pub fn signed_batch_item(&self, secret: &str) -> serde_json::Value {
+        #[expect(clippy::unwrap_used)]
+        let mut item = serde_json::to_value(self).unwrap();
+        item["signature"] = self.signature(secret).into();
+
+        item
+    }
+}
+
+pub fn request_signature(
+    secret: &str,
+    method: &str,
+    path: &str,
+    timestamp: &str,
+    body: &str,
+) -> String {
+    type HmacSha256 = Hmac<Sha256>;
+
+    #[expect(clippy::unwrap_used)]
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{method}\n{path}\n{timestamp}\n{body}").as_bytes());
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
 #[allow(dead_code)]
 pub struct TestApplication {
     pub base_url: String,
@@ -31,6 +166,39 @@ pub struct TestApplication {
     pub db: PgPool,
 }
 
+impl Drop for TestApplication {
+    /// The server continues to run on its own worker threads after a test completes, keeping its
+    /// database connections open. They are terminated so that the tests do not exhaust the
+    /// connection limit of the database server.
+    fn drop(&mut self) {
+        let url = self.settings.database.url.clone();
+
+        let terminate = move || -> anyhow::Result<()> {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+
+            runtime.block_on(async {
+                let mut connection = PgConnection::connect(&url).await?;
+
+                sqlx::query(
+                    "SELECT pg_terminate_backend(pid) FROM pg_stat_activity
+                    WHERE datname = current_database() AND pid <> pg_backend_pid()",
+                )
+                .execute(&mut connection)
+                .await?;
+
+                connection.close().await?;
+
+                Ok(())
+            })
+        };
+
+        // A runtime cannot be started from within the runtime of the test.
+        std::thread::spawn(terminate).join().ok();
+    }
+}
+
 impl TestApplication {
     pub async fn create_random_device(&self) -> Result<(String, String), sqlx::Error> {
         let id = Uuid::new_v4();
@@ -49,6 +217,31 @@ pub async fn create_random_device(&self) -> Result<(String, String), sqlx::Error
         Ok((api_key, api_secret))
     }
 
+    pub async fn enable_osmand(&self, api_key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE devices SET osmand_enabled = TRUE WHERE api_key = $1",
+            api_key
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn add_friend(&self, api_key: &str, friend_api_key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO device_friends (device_id, friend_id)
+            SELECT device.id, friend.id FROM devices device, devices friend
+            WHERE device.api_key = $1 AND friend.api_key = $2"#,
+            api_key,
+            friend_api_key
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
     #[expect(clippy::expect_used)]
     pub async fn post_report(
         &self,
@@ -69,6 +262,97 @@ pub async fn post_report(
             .await
             .expect("Failed to execute request")
     }
+
+    /// Sends a POST request signed with a version 2 signature.
+    #[expect(clippy::expect_used)]
+    pub async fn post_signed(&self, path: &str, api_secret: &str, body: &str) -> reqwest::Response {
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp().to_string();
+        let signature = request_signature(api_secret, "POST", path, &timestamp, body);
+
+        reqwest::Client::new()
+            .post(format!("{}{path}", self.base_url))
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .header("X-Signature", signature)
+            .header("X-Signature-Timestamp", timestamp)
+            .header("X-Signature-Version", "2")
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    /// Sends a DELETE request signed with a version 2 signature.
+    #[expect(clippy::expect_used)]
+    pub async fn delete_signed(&self, path: &str, api_secret: &str) -> reqwest::Response {
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp().to_string();
+        let signature = request_signature(api_secret, "DELETE", path, &timestamp, "");
+
+        reqwest::Client::new()
+            .delete(format!("{}{path}", self.base_url))
+            .header("X-Signature", signature)
+            .header("X-Signature-Timestamp", timestamp)
+            .header("X-Signature-Version", "2")
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    #[expect(clippy::expect_used)]
+    pub async fn post_report_batch(&self, api_key: &str, body: &str) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!(
+                "{}/api/v1/devices/{api_key}/reports/batch",
+                self.base_url
+            ))
+            .header("Content-Type", "application/json")
+            .header("Accept", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    /// Submits a sample report with a version 1 signature, returning the ID of the created report.
+    #[expect(clippy::expect_used)]
+    pub async fn submit_report(&self, api_key: &str, api_secret: &str, timestamp: &str) -> String {
+        let report = ReportRequest::sample(timestamp);
+        let body = serde_json::to_string(&report).expect("Failed to serialize report");
+
+        let response = self
+            .post_report(api_key, &report.signature(api_secret), &body)
+            .await;
+
+        assert_eq!(201, response.status().as_u16());
+
+        let report: serde_json::Value = response.json().await.expect("Failed to parse report");
+
+        report["id"]
+            .as_str()
+            .expect("The API did not return a report ID")
+            .to_string()
+    }
+
+    /// Submits reports in a single signed batch, asserting that every report is accepted.
+    #[expect(clippy::expect_used)]
+    pub async fn submit_reports(&self, api_key: &str, api_secret: &str, reports: &[ReportRequest]) {
+        let body = serde_json::Value::Array(
+            reports
+                .iter()
+                .map(|report| report.signed_batch_item(api_secret))
+                .collect(),
+        )
+        .to_string();
+
+        let response = self.post_report_batch(api_key, &body).await;
+
+        assert_eq!(200, response.status().as_u16());
+
+        let body: serde_json::Value = response.json().await.expect("Failed to parse batch result");
+
+        assert_eq!(reports.len(), body["accepted"]);
+        assert_eq!(0, body["rejected"]);
+    }
 }
 
 #[expect(clippy::expect_used)]
@@ -82,6 +366,12 @@ pub async fn run_server() -> TestApplication {
 
         settings.application.address = "127.0.0.1".to_string();
         settings.application.port = 0;
+        settings.import.directory = std::env::temp_dir()
+            .join("com_calindora_follow_test_imports")
+            .to_string_lossy()
+            .to_string();
+        settings.live.heartbeat_seconds = std::num::NonZeroU32::MIN;
+        settings.reports.max_simplified_reports = 10;
 
         // Parse the configured database URL to extract credentials
         let base_options = PgConnectOptions::from_str(&settings.database.url)
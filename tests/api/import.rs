@@ -0,0 +1,175 @@
+use std::str::FromStr;
+
+use bigdecimal::ToPrimitive;
+use sqlx::types::BigDecimal;
+
+use crate::helpers::run_server;
+
+const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1"
+    xmlns:gpxtpx="http://www.garmin.com/xmlschemas/TrackPointExtension/v2">
+<trk><trkseg>
+<trkpt lat="40.000" lon="-111.000"><ele>1500</ele><time>2015-06-01T12:00:00Z</time></trkpt>
+<trkpt lat="40.001" lon="-111.000"><ele>1501</ele><time>2015-06-01T12:00:10Z</time></trkpt>
+<trkpt lat="40.002" lon="-111.000"><time>2015-06-01T12:00:20Z</time>
+<extensions><gpxtpx:TrackPointExtension><gpxtpx:speed>3.5</gpxtpx:speed><gpxtpx:course>90</gpxtpx:course></gpxtpx:TrackPointExtension></extensions></trkpt>
+<trkpt lat="95.000" lon="-111.000"><time>2015-06-01T12:00:30Z</time></trkpt>
+<trkpt lat="40.003" lon="-111.000"></trkpt>
+</trkseg></trk>
+</gpx>"#;
+
+const KML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+<Document>
+<Placemark>
+<gx:Track>
+<when>2015-06-02T08:00:00Z</when>
+<when>2015-06-02T08:01:00Z</when>
+<gx:coord>-111.0 40.0 1500</gx:coord>
+<gx:coord>-111.0 40.01 1510</gx:coord>
+</gx:Track>
+</Placemark>
+<Placemark>
+<TimeStamp><when>2015-06-02T09:00:00Z</when></TimeStamp>
+<Point><coordinates>-111.5,40.5,1400</coordinates></Point>
+</Placemark>
+<Placemark>
+<Point><coordinates>-111.5,40.5,1400</coordinates></Point>
+</Placemark>
+</Document>
+</kml>"#;
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn import_gpx_creates_reports() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = server
+        .post_signed(
+            &format!("/api/v1/devices/{api_key}/import"),
+            &api_secret,
+            GPX,
+        )
+        .await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        serde_json::json!({ "imported": 3, "duplicates": 0, "rejected": 2 }),
+        body
+    );
+
+    let reports = sqlx::query!("SELECT * FROM reports ORDER BY timestamp")
+        .fetch_all(&server.db)
+        .await
+        .expect("Failed to fetch imported reports");
+
+    assert_eq!(3, reports.len());
+
+    // The speed and bearing of the second point are derived from the first point.
+    let speed = reports[1].speed.to_f64().unwrap();
+    assert!((speed - 11.12).abs() < 0.01, "Unexpected speed {speed}");
+    assert!(reports[1].bearing.to_f64().unwrap() < 0.01);
+
+    assert_eq!(BigDecimal::from_str("3.5").unwrap(), reports[2].speed);
+    assert_eq!(BigDecimal::from(90), reports[2].bearing);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn import_kml_skips_duplicate_timestamps() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let path = format!("/api/v1/devices/{api_key}/import");
+
+    let response = server.post_signed(&path, &api_secret, KML).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        serde_json::json!({ "imported": 3, "duplicates": 0, "rejected": 0 }),
+        body
+    );
+
+    // A different body is required, as each signature may only be used once.
+    let response = server
+        .post_signed(&path, &api_secret, &format!("{KML}\n"))
+        .await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        serde_json::json!({ "imported": 0, "duplicates": 3, "rejected": 0 }),
+        body
+    );
+
+    let count = sqlx::query_scalar!("SELECT COUNT(*) FROM reports")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to count imported reports");
+
+    assert_eq!(Some(3), count);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn import_returns_400_for_invalid_document() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let path = format!("/api/v1/devices/{api_key}/import");
+
+    for body in ["not xml", "<html></html>"] {
+        let response = server.post_signed(&path, &api_secret, body).await;
+
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not return a 400 Bad Request for {body}"
+        );
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn import_requires_version_2_signature() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}/api/v1/devices/{api_key}/import",
+            server.base_url
+        ))
+        .body(GPX)
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(400, response.status().as_u16());
+
+    let response = server
+        .post_signed(&format!("/api/v1/devices/{api_key}/import"), "wrong", GPX)
+        .await;
+
+    assert_eq!(401, response.status().as_u16());
+}
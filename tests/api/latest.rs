@@ -0,0 +1,136 @@
+use time::OffsetDateTime;
+
+use com_calindora_follow::util::TIMESTAMP_FORMAT;
+
+use crate::helpers::{TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn get_latest(
+    server: &TestApplication,
+    api_key: &str,
+    header: Option<(&str, &str)>,
+) -> reqwest::Response {
+    let mut request = reqwest::Client::new().get(format!(
+        "{}/api/v1/devices/{api_key}/latest",
+        server.base_url
+    ));
+
+    if let Some((name, value)) = header {
+        request = request.header(name, value);
+    }
+
+    request.send().await.expect("Failed to execute request")
+}
+
+fn header(response: &reqwest::Response, name: &str) -> String {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_latest_report_supports_conditional_requests() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let now = OffsetDateTime::now_utc();
+    let earlier = (now - time::Duration::minutes(1))
+        .format(TIMESTAMP_FORMAT)
+        .expect("Failed to format timestamp");
+    let later = now
+        .format(TIMESTAMP_FORMAT)
+        .expect("Failed to format timestamp");
+
+    server.submit_report(&api_key, &api_secret, &later).await;
+    server.submit_report(&api_key, &api_secret, &earlier).await;
+
+    let response = get_latest(&server, &api_key, None).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let etag = header(&response, "ETag");
+    let last_modified = header(&response, "Last-Modified");
+
+    assert!(!etag.is_empty());
+    assert!(!last_modified.is_empty());
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+
+    assert_eq!(false, body["stale"]);
+    assert!(body["stale_at"].is_string());
+    assert_eq!("40", body["report"]["latitude"]);
+    assert_eq!(1, body["report"]["sequence"]);
+
+    let response = get_latest(&server, &api_key, Some(("If-None-Match", &etag))).await;
+
+    assert_eq!(304, response.status().as_u16());
+    assert_eq!(etag, header(&response, "ETag"));
+
+    let response = get_latest(
+        &server,
+        &api_key,
+        Some(("If-Modified-Since", &last_modified)),
+    )
+    .await;
+
+    assert_eq!(304, response.status().as_u16());
+
+    // A newer report changes the response.
+    let newest = (now + time::Duration::seconds(1))
+        .format(TIMESTAMP_FORMAT)
+        .expect("Failed to format timestamp");
+    server.submit_report(&api_key, &api_secret, &newest).await;
+
+    let response = get_latest(&server, &api_key, Some(("If-None-Match", &etag))).await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert_ne!(etag, header(&response, "ETag"));
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_latest_report_marks_old_reports_stale() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_report(&api_key, &api_secret, "2023-01-01T00:00:00+00:00")
+        .await;
+
+    let response = get_latest(&server, &api_key, None).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse response");
+
+    assert_eq!(true, body["stale"]);
+    assert_eq!("+002023-01-01T00:05:00.000000000Z", body["stale_at"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_latest_report_returns_404_without_reports() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = get_latest(&server, &api_key, None).await;
+
+    assert_eq!(404, response.status().as_u16());
+
+    let response = get_latest(&server, "unknown", None).await;
+
+    assert_eq!(404, response.status().as_u16());
+}
@@ -1,3 +1,22 @@
+mod colocation;
+mod export;
+mod geofences;
 mod health_check;
 mod helpers;
+mod import;
+mod latest;
+mod osmand;
+mod owntracks;
+mod places;
+mod proximity;
 mod reports;
+mod settings;
+mod signatures;
+mod spatial;
+mod statistics;
+mod stays;
+mod stream;
+mod summaries;
+mod takeout;
+mod trips;
+mod websocket;
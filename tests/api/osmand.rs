@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use sqlx::types::BigDecimal;
+use time::OffsetDateTime;
+
+use crate::helpers::run_server;
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn osmand_report_is_persisted_for_enabled_device() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .enable_osmand(&api_key)
+        .await
+        .expect("Failed to enable OsmAnd for the test device");
+
+    let response = reqwest::Client::new()
+        .post(format!(
+            "{}/api/v1/osmand?id={api_key}&lat=12.5&lon=-45.25&timestamp=1639577716&altitude=100&speed=10&bearing=90&accuracy=5&batt=80",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(
+        200,
+        response.status().as_u16(),
+        "The API did not return a 200 OK for a valid OsmAnd report",
+    );
+
+    let report = sqlx::query!("SELECT * FROM reports")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to fetch submitted report");
+
+    assert_eq!(
+        report.timestamp,
+        OffsetDateTime::from_unix_timestamp(1_639_577_716).unwrap()
+    );
+    assert_eq!(report.latitude, BigDecimal::from_str("12.5").unwrap());
+    assert_eq!(report.longitude, BigDecimal::from_str("-45.25").unwrap());
+    assert_eq!(report.altitude, BigDecimal::from_str("100").unwrap());
+    assert_eq!(
+        report.speed,
+        BigDecimal::from_str("5.144444444444").unwrap(),
+        "The speed was not converted from knots to meters per second",
+    );
+    assert_eq!(report.bearing, BigDecimal::from_str("90").unwrap());
+    assert_eq!(report.accuracy, BigDecimal::from_str("5").unwrap());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn osmand_report_returns_403_for_disabled_device() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = reqwest::Client::new()
+        .get(format!(
+            "{}/api/v1/osmand?id={api_key}&lat=12.5&lon=-45.25",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(
+        403,
+        response.status().as_u16(),
+        "The API did not return a 403 Forbidden for a device without OsmAnd enabled",
+    );
+
+    assert_eq!(
+        "application/json",
+        response.headers().get("Content-Type").unwrap(),
+        "The API did not return a JSON error response for a device without OsmAnd enabled",
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn osmand_report_returns_400_for_invalid_report() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .enable_osmand(&api_key)
+        .await
+        .expect("Failed to enable OsmAnd for the test device");
+
+    let test_cases = vec![
+        ("lat=90.1&lon=0", "latitude greater than 90.0"),
+        ("lat=0&lon=-180.1", "longitude less than -180.0"),
+        ("lat=0&lon=0&speed=-1", "negative speed"),
+        ("lat=0&lon=0&timestamp=yesterday", "invalid timestamp"),
+        ("lat=north&lon=0", "non-numeric latitude"),
+        ("lon=0", "missing latitude"),
+    ];
+
+    for (query, description) in test_cases {
+        let response = reqwest::Client::new()
+            .post(format!(
+                "{}/api/v1/osmand?id={api_key}&{query}",
+                server.base_url
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not return a 400 Bad Request for an invalid OsmAnd report: {description}",
+        );
+    }
+}
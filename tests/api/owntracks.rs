@@ -0,0 +1,188 @@
+use std::str::FromStr;
+
+use sqlx::types::BigDecimal;
+
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn post_owntracks_message(
+    server: &TestApplication,
+    api_key: &str,
+    api_secret: &str,
+    body: &serde_json::Value,
+) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{}/api/v1/owntracks", server.base_url))
+        .basic_auth(api_key, Some(api_secret))
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn owntracks_location_is_persisted() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let message = serde_json::json!({
+        "_type": "location",
+        "tid": "ME",
+        "tst": 1_639_577_716,
+        "lat": 37.7749,
+        "lon": -122.4194,
+        "alt": 15,
+        "vel": 36,
+        "cog": 270,
+        "acc": 8,
+        "batt": 77,
+    });
+
+    let response = post_owntracks_message(&server, &api_key, &api_secret, &message).await;
+
+    assert_eq!(
+        200,
+        response.status().as_u16(),
+        "The API did not return a 200 OK for a valid OwnTracks location",
+    );
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(serde_json::json!([]), body);
+
+    let report = sqlx::query!("SELECT * FROM reports")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to fetch submitted report");
+
+    assert_eq!(report.latitude, BigDecimal::from_str("37.7749").unwrap());
+    assert_eq!(report.longitude, BigDecimal::from_str("-122.4194").unwrap());
+    assert_eq!(report.altitude, BigDecimal::from_str("15").unwrap());
+    assert_eq!(
+        report.speed,
+        BigDecimal::from_str("10").unwrap(),
+        "The speed was not converted from kilometers per hour to meters per second",
+    );
+    assert_eq!(report.bearing, BigDecimal::from_str("270").unwrap());
+    assert_eq!(report.accuracy, BigDecimal::from_str("8").unwrap());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn owntracks_response_contains_friend_locations() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+    let (friend_api_key, friend_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+    let (stranger_api_key, stranger_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .add_friend(&api_key, &friend_api_key)
+        .await
+        .expect("Failed to add a friend to the test device");
+
+    for (key, secret) in [
+        (&friend_api_key, &friend_api_secret),
+        (&stranger_api_key, &stranger_api_secret),
+    ] {
+        let request =
+            ReportRequest::new("2021-12-15T14:15:16+00:00", 12.5, 45.0, 0.0, 5.0, 0.0, 3.0);
+        let body = serde_json::to_string(&request).unwrap();
+        server
+            .post_report(key, &request.signature(secret), &body)
+            .await;
+    }
+
+    let message = serde_json::json!({ "_type": "lwt", "tst": 1_639_577_716 });
+    let response = post_owntracks_message(&server, &api_key, &api_secret, &message).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    let messages = body.as_array().unwrap();
+
+    assert_eq!(
+        2,
+        messages.len(),
+        "The API did not return exactly one card and location for the single friend",
+    );
+
+    let location = messages
+        .iter()
+        .find(|message| message["_type"] == "location")
+        .unwrap();
+
+    let card = messages
+        .iter()
+        .find(|message| message["_type"] == "card")
+        .unwrap();
+
+    // The API key of a friend is a credential, and must not be revealed to the app.
+    assert!(
+        messages
+            .iter()
+            .all(|message| !message.to_string().contains(friend_api_key.as_str()))
+    );
+    assert!(
+        location["topic"]
+            .as_str()
+            .unwrap()
+            .starts_with("owntracks/follow/")
+    );
+    assert_eq!(card["topic"], location["topic"]);
+    assert_eq!(card["tid"], location["tid"]);
+    assert_eq!(1_639_577_716, location["tst"]);
+    assert_eq!(serde_json::json!(12.5), location["lat"]);
+    assert_eq!(serde_json::json!(45.0), location["lon"]);
+    assert_eq!(18, location["vel"]);
+
+    let count = sqlx::query_scalar!("SELECT COUNT(*) FROM reports")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to count submitted reports");
+
+    assert_eq!(
+        Some(2),
+        count,
+        "A report was created for a message that was not a location",
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn owntracks_returns_401_for_invalid_credentials() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let message = serde_json::json!({ "_type": "location", "tst": 0, "lat": 0, "lon": 0 });
+
+    let response = post_owntracks_message(&server, &api_key, "wrong", &message).await;
+    assert_eq!(401, response.status().as_u16());
+
+    let response = post_owntracks_message(&server, "unknown", "wrong", &message).await;
+    assert_eq!(401, response.status().as_u16());
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/api/v1/owntracks", server.base_url))
+        .json(&message)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(401, response.status().as_u16());
+}
@@ -0,0 +1,300 @@
+use serde_json::json;
+
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+/// A range including all of the reports.
+const RANGE: &str = "since=2022-12-31T00:00:00Z&until=2023-01-02T00:00:00Z";
+
+/// Ten minutes at home, twenty at work, driving past home, then another ten at home.
+fn report_requests() -> Vec<ReportRequest> {
+    let mut reports = Vec::new();
+
+    for (start, end, latitude) in [
+        (0, 10, 40.0),
+        (20, 40, 40.05),
+        (50, 50, 40.0),
+        (55, 55, 39.9),
+        (60, 70, 40.0),
+    ] {
+        for minute in (start..=end).step_by(2) {
+            let timestamp = format!("2023-01-01T{:02}:{:02}:00+00:00", minute / 60, minute % 60);
+            reports.push(ReportRequest::at(&timestamp, latitude, -105.0));
+        }
+    }
+
+    reports
+}
+
+#[expect(clippy::expect_used)]
+async fn create_place(
+    server: &TestApplication,
+    api_key: &str,
+    api_secret: &str,
+    name: &str,
+    latitude: f64,
+    radius: f64,
+) -> serde_json::Value {
+    let body = json!({
+        "name": name,
+        "latitude": latitude,
+        "longitude": -105.0,
+        "radius": radius,
+    })
+    .to_string();
+
+    let response = server
+        .post_signed(
+            &format!("/api/v1/devices/{api_key}/places"),
+            api_secret,
+            &body,
+        )
+        .await;
+
+    assert_eq!(201, response.status().as_u16());
+
+    response.json().await.expect("Failed to parse place")
+}
+
+#[expect(clippy::expect_used)]
+async fn get(server: &TestApplication, path: &str) -> Vec<serde_json::Value> {
+    reqwest::get(format!("{}{path}", server.base_url))
+        .await
+        .expect("Failed to execute request")
+        .json()
+        .await
+        .expect("Failed to parse response")
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn places_record_visits() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let home = create_place(&server, &api_key, &api_secret, "Home", 40.0, 200.0).await;
+    let work = create_place(&server, &api_key, &api_secret, "Work", 40.05, 200.0).await;
+    create_place(&server, &api_key, &api_secret, "School", 39.9, 200.0).await;
+
+    let places = get(&server, &format!("/api/v1/devices/{api_key}/places")).await;
+
+    assert_eq!(
+        vec!["Home", "School", "Work"],
+        places
+            .iter()
+            .map(|place| place["name"].as_str().unwrap_or_default())
+            .collect::<Vec<_>>()
+    );
+
+    let visits = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/places/visits?{RANGE}"),
+    )
+    .await;
+
+    assert_eq!(3, visits.len());
+    assert_eq!(home["id"], visits[0]["place_id"]);
+    assert_eq!("Home", visits[0]["name"]);
+    assert_eq!(json!(600.0), visits[0]["duration"]);
+    assert_eq!(work["id"], visits[1]["place_id"]);
+    assert_eq!(
+        "00:20",
+        &visits[1]["arrival"].as_str().unwrap_or_default()[14..19]
+    );
+    assert_eq!(
+        "00:40",
+        &visits[1]["departure"].as_str().unwrap_or_default()[14..19]
+    );
+    assert_eq!(json!(1200.0), visits[1]["duration"]);
+    assert_eq!(home["id"], visits[2]["place_id"]);
+
+    let time = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/places/time?{RANGE}"),
+    )
+    .await;
+
+    assert_eq!(3, time.len());
+    assert_eq!("Home", time[0]["name"]);
+    assert_eq!(2, time[0]["visits"]);
+    assert_eq!(json!(1200.0), time[0]["duration"]);
+    assert_eq!("School", time[1]["name"]);
+    assert_eq!(0, time[1]["visits"]);
+    assert_eq!(json!(0.0), time[1]["duration"]);
+    assert_eq!("Work", time[2]["name"]);
+    assert_eq!(1, time[2]["visits"]);
+
+    let time = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/places/time?since=2023-01-01T00:15:00Z&until=2023-01-02T00:00:00Z"),
+    )
+    .await;
+
+    assert_eq!(1, time[0]["visits"]);
+    assert_eq!(json!(600.0), time[0]["duration"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn places_record_visits_throughout_their_radius() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // Wandering for half an hour about 300 m either side of the center of a large place, never
+    // remaining near any one position long enough for a stay, then leaving it.
+    let mut reports = Vec::new();
+
+    for minute in (0..=30).step_by(2) {
+        let latitude = if minute % 4 == 0 { 40.003 } else { 39.997 };
+        let timestamp = format!("2023-01-01T00:{minute:02}:00+00:00");
+        reports.push(ReportRequest::at(&timestamp, latitude, -105.0));
+    }
+
+    reports.push(ReportRequest::at("2023-01-01T00:32:00+00:00", 40.1, -105.0));
+
+    server.submit_reports(&api_key, &api_secret, &reports).await;
+
+    let campus = create_place(&server, &api_key, &api_secret, "Campus", 40.0, 500.0).await;
+
+    let stays = get(&server, &format!("/api/v1/devices/{api_key}/stays?{RANGE}")).await;
+
+    assert!(stays.is_empty());
+
+    let visits = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/places/visits?{RANGE}"),
+    )
+    .await;
+
+    assert_eq!(1, visits.len());
+    assert_eq!(campus["id"], visits[0]["place_id"]);
+    assert_eq!(json!(1800.0), visits[0]["duration"]);
+
+    let time = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/places/time?{RANGE}"),
+    )
+    .await;
+
+    assert_eq!(1, time[0]["visits"]);
+    assert_eq!(json!(1800.0), time[0]["duration"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn place_visits_require_a_bounded_range() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    for query in [
+        "",
+        "?until=2023-01-01T00:00:00Z",
+        "?since=2023-01-01T00:00:00Z&until=2024-01-03T00:00:00Z",
+    ] {
+        for path in ["visits", "time"] {
+            let response = reqwest::get(format!(
+                "{}/api/v1/devices/{api_key}/places/{path}{query}",
+                server.base_url
+            ))
+            .await
+            .expect("Failed to execute request");
+
+            assert_eq!(400, response.status().as_u16());
+        }
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn places_can_be_deleted() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let place = create_place(&server, &api_key, &api_secret, "Home", 40.0, 200.0).await;
+    let path = format!(
+        "/api/v1/devices/{api_key}/places/{}",
+        place["id"].as_str().unwrap_or_default()
+    );
+
+    let response = server.delete_signed(&path, &api_secret).await;
+    assert_eq!(204, response.status().as_u16());
+
+    let response = server
+        .delete_signed(
+            &format!("/api/v1/devices/{api_key}/places/{}", uuid::Uuid::new_v4()),
+            &api_secret,
+        )
+        .await;
+    assert_eq!(404, response.status().as_u16());
+
+    let places = get(&server, &format!("/api/v1/devices/{api_key}/places")).await;
+    assert!(places.is_empty());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_place_rejects_invalid_places() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    create_place(&server, &api_key, &api_secret, "Home", 40.0, 200.0).await;
+
+    let path = format!("/api/v1/devices/{api_key}/places");
+
+    for (body, status) in [
+        (
+            json!({"name": "Home", "latitude": 41.0, "longitude": -105.0, "radius": 50.0}),
+            409,
+        ),
+        (
+            json!({"name": "", "latitude": 41.0, "longitude": -105.0, "radius": 50.0}),
+            400,
+        ),
+        (
+            json!({"name": "Work", "latitude": 91.0, "longitude": -105.0, "radius": 50.0}),
+            400,
+        ),
+        (
+            json!({"name": "Work", "latitude": 41.0, "longitude": -105.0, "radius": 0.0}),
+            400,
+        ),
+    ] {
+        let response = server
+            .post_signed(&path, &api_secret, &body.to_string())
+            .await;
+
+        assert_eq!(status, response.status().as_u16(), "{body}");
+    }
+
+    // Places may only be changed with a version 2 signature.
+    let response = reqwest::Client::new()
+        .post(format!("{}{path}", server.base_url))
+        .header("Content-Type", "application/json")
+        .body(
+            json!({"name": "Work", "latitude": 41.0, "longitude": -105.0, "radius": 50.0})
+                .to_string(),
+        )
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(400, response.status().as_u16());
+}
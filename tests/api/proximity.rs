@@ -0,0 +1,149 @@
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn get_proximity(server: &TestApplication, api_key: &str, query: &str) -> reqwest::Response {
+    reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/proximity?{query}",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_proximity_returns_intervals_within_radius() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // Roughly 0, 0, 11 km, 55 m and 11 km from the point.
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -111.5),
+                ReportRequest::at("2023-01-01T00:01:00+00:00", 40.0, -111.5),
+                ReportRequest::at("2023-01-01T00:02:00+00:00", 40.1, -111.5),
+                ReportRequest::at("2023-01-01T00:03:00+00:00", 40.0005, -111.5),
+                ReportRequest::at("2023-01-01T00:04:00+00:00", 40.1, -111.5),
+            ],
+        )
+        .await;
+
+    let response =
+        get_proximity(&server, &api_key, "latitude=40&longitude=-111.5&radius=100").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let intervals: Vec<serde_json::Value> =
+        response.json().await.expect("Failed to parse intervals");
+
+    assert_eq!(2, intervals.len());
+
+    assert_eq!("+002023-01-01T00:00:00.000000000Z", intervals[0]["entered"]);
+    assert_eq!("+002023-01-01T00:01:00.000000000Z", intervals[0]["exited"]);
+    assert_eq!(serde_json::json!(0.0), intervals[0]["min_distance"]);
+    assert_eq!(2, intervals[0]["reports"]);
+
+    assert_eq!("+002023-01-01T00:03:00.000000000Z", intervals[1]["entered"]);
+    assert_eq!("+002023-01-01T00:03:00.000000000Z", intervals[1]["exited"]);
+    assert!(
+        intervals[1]["min_distance"]
+            .as_f64()
+            .is_some_and(|distance| (55.0..56.0).contains(&distance))
+    );
+    assert_eq!(1, intervals[1]["reports"]);
+
+    let intervals: Vec<serde_json::Value> = get_proximity(
+        &server,
+        &api_key,
+        "latitude=40&longitude=-111.5&radius=100&since=2023-01-01T00:02:00Z",
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse intervals");
+
+    assert_eq!(1, intervals.len());
+    assert_eq!("+002023-01-01T00:03:00.000000000Z", intervals[0]["entered"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_proximity_handles_the_antimeridian_and_distant_reports() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // Roughly 11 m, 11 m, far away twice, and 11 m from the point.
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:00:00+00:00", 0.0, 179.9999),
+                ReportRequest::at("2023-01-01T00:01:00+00:00", 0.0, -179.9999),
+                ReportRequest::at("2023-01-01T00:02:00+00:00", 0.0, 0.0),
+                ReportRequest::at("2023-01-01T00:03:00+00:00", 45.0, 90.0),
+                ReportRequest::at("2023-01-01T00:04:00+00:00", 0.0, -179.9999),
+            ],
+        )
+        .await;
+
+    let intervals: Vec<serde_json::Value> =
+        get_proximity(&server, &api_key, "latitude=0&longitude=180&radius=100")
+            .await
+            .json()
+            .await
+            .expect("Failed to parse intervals");
+
+    assert_eq!(2, intervals.len());
+
+    assert_eq!("+002023-01-01T00:00:00.000000000Z", intervals[0]["entered"]);
+    assert_eq!("+002023-01-01T00:01:00.000000000Z", intervals[0]["exited"]);
+    assert_eq!(2, intervals[0]["reports"]);
+
+    assert_eq!("+002023-01-01T00:04:00.000000000Z", intervals[1]["entered"]);
+    assert_eq!(1, intervals[1]["reports"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_proximity_returns_400_for_invalid_parameters() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    for query in [
+        "latitude=40&longitude=-111.5",
+        "latitude=40&longitude=-111.5&radius=0",
+        "latitude=91&longitude=-111.5&radius=100",
+        "latitude=40&longitude=-111.5&radius=NaN",
+    ] {
+        let response = get_proximity(&server, &api_key, query).await;
+
+        assert_eq!(400, response.status().as_u16(), "{query}");
+    }
+}
+
+#[actix_web::test]
+async fn get_proximity_returns_404_for_unknown_device() {
+    let server = run_server().await;
+
+    let response = get_proximity(
+        &server,
+        "unknown",
+        "latitude=40&longitude=-111.5&radius=100",
+    )
+    .await;
+
+    assert_eq!(404, response.status().as_u16());
+}
@@ -1,66 +1,10 @@
 use std::str::FromStr;
 
 use com_calindora_follow::util::TIMESTAMP_FORMAT;
-use hmac::{Hmac, KeyInit, Mac};
-use sha2::Sha256;
 use sqlx::types::BigDecimal;
-use time::OffsetDateTime;
-
-use crate::helpers::run_server;
-
-#[derive(serde::Serialize)]
-struct ReportRequest {
-    pub timestamp: String,
-    pub latitude: String,
-    pub longitude: String,
-    pub altitude: String,
-    pub speed: String,
-    pub bearing: String,
-    pub accuracy: String,
-}
-
-impl ReportRequest {
-    fn new(
-        timestamp: &str,
-        latitude: f64,
-        longitude: f64,
-        altitude: f64,
-        speed: f64,
-        bearing: f64,
-        accuracy: f64,
-    ) -> Self {
-        Self {
-            timestamp: timestamp.to_string(),
-            latitude: format!("{latitude:.12}"),
-            longitude: format!("{longitude:.12}"),
-            altitude: format!("{altitude:.12}"),
-            speed: format!("{speed:.12}"),
-            bearing: format!("{bearing:.12}"),
-            accuracy: format!("{accuracy:.12}"),
-        }
-    }
-
-    fn signature(&self, secret: &str) -> String {
-        type HmacSha256 = Hmac<Sha256>;
-
-        let input = format!(
-            "{}{}{}{}{}{}{}",
-            self.timestamp,
-            self.latitude,
-            self.longitude,
-            self.altitude,
-            self.speed,
-            self.bearing,
-            self.accuracy
-        );
+use time::{Duration, OffsetDateTime};
 
-        #[expect(clippy::unwrap_used)]
-        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
-        mac.update(input.as_bytes());
-
-        hex::encode(mac.finalize().into_bytes())
-    }
-}
+use crate::helpers::{ReportRequest, run_server};
 
 #[actix_web::test]
 #[expect(clippy::expect_used)]
@@ -328,3 +272,647 @@ async fn post_report_fails_if_fatal_database_error() {
         "The API did not return a JSON error reponse after a fatal database error",
     );
 }
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_batch_returns_per_item_results() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let valid = ReportRequest::new("2021-12-15T14:15:16+00:00", 0.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+    let invalid = ReportRequest::new("2021-12-15T14:15:17+00:00", 91.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+    let unsigned = ReportRequest::new("2021-12-15T14:15:18+00:00", 0.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+    let mut forged = valid.signed_batch_item(&api_secret);
+    forged["latitude"] = "10.000000000000".into();
+
+    let body = serde_json::json!([
+        valid.signed_batch_item(&api_secret),
+        invalid.signed_batch_item(&api_secret),
+        serde_json::to_value(&unsigned).unwrap(),
+        forged,
+    ])
+    .to_string();
+
+    let response = server.post_report_batch(&api_key, &body).await;
+
+    assert_eq!(
+        200,
+        response.status().as_u16(),
+        "The API did not return a 200 OK for a report batch",
+    );
+
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(1, body["accepted"]);
+    assert_eq!(3, body["rejected"]);
+
+    let statuses: Vec<&str> = body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["status"].as_str().unwrap())
+        .collect();
+
+    assert_eq!(
+        vec!["accepted", "rejected", "rejected", "rejected"],
+        statuses
+    );
+
+    let count = sqlx::query_scalar!("SELECT COUNT(*) FROM reports")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to count submitted reports");
+
+    assert_eq!(Some(1), count);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_batch_returns_400_for_invalid_batch() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let test_cases = vec![
+        ("[]".to_string(), "empty batch"),
+        ("{}".to_string(), "non-array batch"),
+        (
+            serde_json::to_string(&vec![serde_json::json!({}); 501]).unwrap(),
+            "oversized batch",
+        ),
+    ];
+
+    for (body, description) in test_cases {
+        let response = server.post_report_batch(&api_key, &body).await;
+
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not return a 400 Bad Request for an invalid batch: {description}",
+        );
+
+        assert_eq!(
+            "application/json",
+            response.headers().get("Content-Type").unwrap(),
+            "The API did not return a JSON error reponse for an invalid batch: {description}",
+        );
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_returns_409_for_reused_nonce() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let request = ReportRequest::with_nonce(OffsetDateTime::now_utc(), "nonce-0123456789");
+
+    let body = serde_json::to_string(&request).unwrap();
+    let signature = request.signature(&api_secret);
+
+    let response = server.post_report(&api_key, &signature, &body).await;
+
+    assert_eq!(
+        201,
+        response.status().as_u16(),
+        "The API did not return a 201 Created for the first use of a nonce",
+    );
+
+    let response = server.post_report(&api_key, &signature, &body).await;
+
+    assert_eq!(
+        409,
+        response.status().as_u16(),
+        "The API did not return a 409 Conflict for a reused nonce",
+    );
+
+    assert_eq!(
+        "application/json",
+        response.headers().get("Content-Type").unwrap(),
+        "The API did not return a JSON error reponse for a reused nonce",
+    );
+
+    let (other_api_key, other_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = server
+        .post_report(&other_api_key, &request.signature(&other_api_secret), &body)
+        .await;
+
+    assert_eq!(
+        201,
+        response.status().as_u16(),
+        "The API did not allow a nonce used by one device to be used by another",
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_returns_401_for_tampered_nonce_report() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let timestamp = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap();
+    let request = ReportRequest::with_nonce(timestamp, "nonce-0123456789");
+    let signature = request.signature(&api_secret);
+
+    let tampered_subsecond = ReportRequest {
+        timestamp: request.timestamp.replace(".000000000", ".500000000"),
+        ..ReportRequest::with_nonce(timestamp, "nonce-0123456789")
+    };
+
+    let tampered_nonce = ReportRequest::with_nonce(timestamp, "nonce-9876543210");
+
+    let expired = ReportRequest::with_nonce(timestamp - Duration::days(365), "nonce-0123456789");
+    let expired_signature = expired.signature(&api_secret);
+
+    let test_cases = vec![
+        (
+            tampered_subsecond,
+            signature.clone(),
+            "altered subsecond timestamp",
+        ),
+        (tampered_nonce, signature, "altered nonce"),
+        (expired, expired_signature, "expired timestamp"),
+    ];
+
+    for (test_case, signature, description) in test_cases {
+        let body = serde_json::to_string(&test_case).unwrap();
+
+        let response = server.post_report(&api_key, &signature, &body).await;
+
+        assert_eq!(
+            401,
+            response.status().as_u16(),
+            "The API did not return a 401 Unauthorized for a report with a nonce: {description}",
+        );
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_returns_original_report_for_duplicate_id() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let id = uuid::Uuid::new_v4().to_string();
+
+    let mut request = ReportRequest::new("2021-12-15T14:15:16+00:00", 0.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+    request.id = Some(id.clone());
+
+    let body = serde_json::to_string(&request).unwrap();
+    let signature = request.signature(&api_secret);
+
+    let response = server.post_report(&api_key, &signature, &body).await;
+    assert_eq!(201, response.status().as_u16());
+
+    let response = server.post_report(&api_key, &signature, &body).await;
+    assert_eq!(
+        200,
+        response.status().as_u16(),
+        "The API did not return a 200 OK for a duplicate report",
+    );
+
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(id, report["id"]);
+
+    let count = sqlx::query_scalar!("SELECT COUNT(*) FROM reports")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to count submitted reports");
+
+    assert_eq!(Some(1), count);
+
+    // A different report with the same ID conflicts with the original.
+    let mut other_request =
+        ReportRequest::new("2021-12-15T14:15:17+00:00", 0.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+    other_request.id = Some(id.clone());
+
+    let response = server
+        .post_report(
+            &api_key,
+            &other_request.signature(&api_secret),
+            &serde_json::to_string(&other_request).unwrap(),
+        )
+        .await;
+
+    assert_eq!(
+        409,
+        response.status().as_u16(),
+        "The API did not return a 409 Conflict for a different report with the same ID",
+    );
+
+    // Report IDs are only unique for each device.
+    let (other_api_key, other_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = server
+        .post_report(&other_api_key, &request.signature(&other_api_secret), &body)
+        .await;
+
+    assert_eq!(
+        201,
+        response.status().as_u16(),
+        "The API did not accept a report ID used by another device",
+    );
+
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(id, report["id"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_returns_original_report_for_duplicate_idempotency_key() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let request = ReportRequest::with_nonce(OffsetDateTime::now_utc(), "nonce-0123456789");
+    let body = serde_json::to_string(&request).unwrap();
+    let signature = request.signature(&api_secret);
+
+    let mut ids = Vec::new();
+
+    for expected_status in [201, 200] {
+        let response = reqwest::Client::new()
+            .post(format!(
+                "{}/api/v1/devices/{api_key}/reports",
+                server.base_url
+            ))
+            .header("Content-Type", "application/json")
+            .header("X-Signature", &signature)
+            .header("Idempotency-Key", "retry-key")
+            .body(body.clone())
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(
+            expected_status,
+            response.status().as_u16(),
+            "The API did not deduplicate a retried report with the same idempotency key",
+        );
+
+        let report: serde_json::Value = response.json().await.unwrap();
+        ids.push(report["id"].as_str().unwrap().to_string());
+    }
+
+    assert_eq!(ids[0], ids[1]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_batch_reports_duplicates() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let mut request = ReportRequest::new("2021-12-15T14:15:16+00:00", 0.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+    request.id = Some(uuid::Uuid::new_v4().to_string());
+
+    let body = serde_json::json!([
+        request.signed_batch_item(&api_secret),
+        request.signed_batch_item(&api_secret),
+    ])
+    .to_string();
+
+    let response = server.post_report_batch(&api_key, &body).await;
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(1, body["accepted"]);
+    assert_eq!(1, body["duplicates"]);
+    assert_eq!("duplicate", body["results"][1]["status"]);
+    assert_eq!(
+        body["results"][0]["report"]["id"],
+        body["results"][1]["report"]["id"]
+    );
+}
+
+/// Retrieves every report by following the `Link` headers of the paginated listing, returning the
+/// IDs and timestamps of the reports in the order they were received.
+#[expect(clippy::expect_used)]
+async fn get_all_report_pages(
+    server: &crate::helpers::TestApplication,
+    api_key: &str,
+    query: &str,
+) -> Vec<(String, String)> {
+    let mut reports = Vec::new();
+    let mut path = format!("/api/v1/devices/{api_key}/reports?{query}");
+
+    loop {
+        let response = reqwest::get(format!("{}{path}", server.base_url))
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(200, response.status().as_u16());
+
+        let next = response
+            .headers()
+            .get("Link")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .strip_prefix('<')
+                    .and_then(|value| value.strip_suffix(">; rel=\"next\""))
+                    .expect("The API returned an invalid Link header")
+                    .to_string()
+            });
+
+        let page: Vec<serde_json::Value> = response.json().await.expect("Failed to parse reports");
+
+        reports.extend(page.iter().map(|report| {
+            (
+                report["id"].as_str().unwrap_or_default().to_string(),
+                report["timestamp"].as_str().unwrap_or_default().to_string(),
+            )
+        }));
+
+        match next {
+            Some(next) => path = next,
+            None => return reports,
+        }
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_pages_through_colliding_timestamps() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let timestamps = [
+        "2023-01-01T00:00:00+00:00",
+        "2023-01-01T00:01:00+00:00",
+        "2023-01-01T00:01:00+00:00",
+        "2023-01-01T00:01:00+00:00",
+        "2023-01-01T00:02:00+00:00",
+    ];
+
+    let body = serde_json::Value::Array(
+        timestamps
+            .iter()
+            .map(|timestamp| {
+                ReportRequest::new(timestamp, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0)
+                    .signed_batch_item(&api_secret)
+            })
+            .collect(),
+    )
+    .to_string();
+
+    let response = server.post_report_batch(&api_key, &body).await;
+    assert_eq!(200, response.status().as_u16());
+
+    let ascending = get_all_report_pages(&server, &api_key, "order=asc&limit=2").await;
+    let mut descending = get_all_report_pages(&server, &api_key, "order=desc&limit=2").await;
+
+    assert_eq!(timestamps.len(), ascending.len());
+
+    let mut ids: Vec<&String> = ascending.iter().map(|(id, _)| id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(timestamps.len(), ids.len());
+
+    assert!(ascending.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+    descending.reverse();
+    assert_eq!(ascending, descending);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_returns_400_for_invalid_cursor() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/reports?cursor=invalid",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request");
+
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_returns_late_reports_changed_since_sequence() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // The second report was recorded before the first, but submitted after it.
+    for timestamp in ["2023-01-01T12:00:00+00:00", "2023-01-01T06:00:00+00:00"] {
+        let report = ReportRequest::new(timestamp, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0);
+        let body = serde_json::to_string(&report).expect("Failed to serialize report");
+
+        let response = server
+            .post_report(&api_key, &report.signature(&api_secret), &body)
+            .await;
+
+        assert_eq!(201, response.status().as_u16());
+    }
+
+    let get_changed = async |changed_since: i64| -> Vec<serde_json::Value> {
+        reqwest::get(format!(
+            "{}/api/v1/devices/{api_key}/reports?changed_since={changed_since}",
+            server.base_url
+        ))
+        .await
+        .expect("Failed to execute request")
+        .json()
+        .await
+        .expect("Failed to parse reports")
+    };
+
+    let reports = get_changed(0).await;
+
+    assert_eq!(2, reports.len());
+    assert_eq!(1, reports[0]["sequence"]);
+    assert_eq!("+002023-01-01T12:00:00.000000000Z", reports[0]["timestamp"]);
+    assert_eq!(2, reports[1]["sequence"]);
+    assert_eq!("+002023-01-01T06:00:00.000000000Z", reports[1]["timestamp"]);
+
+    let reports = get_changed(1).await;
+
+    assert_eq!(1, reports.len());
+    assert_eq!(2, reports[0]["sequence"]);
+
+    assert!(get_changed(2).await.is_empty());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_returns_400_for_changed_since_with_since() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/reports?changed_since=0&since=2023-01-01T00:00:00Z",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request");
+
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_simplifies_track_preserving_trip_ends() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // An L-shaped trip followed an hour later by a straight trip.
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -111.5),
+                ReportRequest::at("2023-01-01T00:01:00+00:00", 40.0, -111.49),
+                ReportRequest::at("2023-01-01T00:02:00+00:00", 40.0, -111.48),
+                ReportRequest::at("2023-01-01T00:03:00+00:00", 40.01, -111.48),
+                ReportRequest::at("2023-01-01T00:04:00+00:00", 40.02, -111.48),
+                ReportRequest::at("2023-01-01T01:00:00+00:00", 40.02, -111.48),
+                ReportRequest::at("2023-01-01T01:01:00+00:00", 40.02, -111.47),
+                ReportRequest::at("2023-01-01T01:02:00+00:00", 40.02, -111.46),
+            ],
+        )
+        .await;
+
+    let get_timestamps = async |query: &str| -> Vec<String> {
+        let reports: Vec<serde_json::Value> = reqwest::get(format!(
+            "{}/api/v1/devices/{api_key}/reports?{query}",
+            server.base_url
+        ))
+        .await
+        .expect("Failed to execute request")
+        .json()
+        .await
+        .expect("Failed to parse reports");
+
+        reports
+            .iter()
+            .map(|report| report["timestamp"].as_str().unwrap_or_default()[14..19].to_string())
+            .collect()
+    };
+
+    assert_eq!(
+        vec!["00:00", "00:02", "00:04", "01:00", "01:02"],
+        get_timestamps("order=asc&simplify=10").await
+    );
+    assert_eq!(
+        vec!["01:02", "01:00", "00:04", "00:02", "00:00"],
+        get_timestamps("max_points=5").await
+    );
+    assert_eq!(
+        vec!["00:00", "00:04", "01:00", "01:02"],
+        get_timestamps("order=asc&max_points=2").await
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_returns_400_for_invalid_simplification() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    for query in ["simplify=0", "max_points=1", "simplify=10&limit=100"] {
+        let response = reqwest::get(format!(
+            "{}/api/v1/devices/{api_key}/reports?{query}",
+            server.base_url
+        ))
+        .await
+        .expect("Failed to execute request");
+
+        assert_eq!(400, response.status().as_u16(), "{query}");
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_returns_400_for_simplifying_too_many_reports() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // The test server simplifies at most ten reports.
+    let reports: Vec<ReportRequest> = (0..11)
+        .map(|minute| {
+            ReportRequest::new(
+                &format!("2023-01-01T00:{minute:02}:00+00:00"),
+                40.0,
+                -111.5,
+                0.0,
+                0.0,
+                0.0,
+                5.0,
+            )
+        })
+        .collect();
+
+    server.submit_reports(&api_key, &api_secret, &reports).await;
+
+    let get = async |query: &str| {
+        reqwest::get(format!(
+            "{}/api/v1/devices/{api_key}/reports?{query}",
+            server.base_url
+        ))
+        .await
+        .expect("Failed to execute request")
+        .status()
+        .as_u16()
+    };
+
+    assert_eq!(400, get("simplify=10").await);
+    assert_eq!(200, get("simplify=10&since=2023-01-01T00:00:00Z").await);
+}
@@ -0,0 +1,25 @@
+use com_calindora_follow::settings::Settings;
+
+#[test]
+#[expect(clippy::expect_used)]
+fn settings_reject_zero_live_intervals() {
+    let load = |key: &str, value: u64| {
+        config::Config::builder()
+            .add_source(config::File::with_name("settings/base.yaml"))
+            .set_override(key, value)
+            .expect("Failed to override setting")
+            .build()
+            .expect("Failed to build settings")
+            .try_deserialize::<Settings>()
+    };
+
+    assert!(load("live.heartbeat_seconds", 30).is_ok());
+
+    for key in [
+        "live.channel_capacity",
+        "live.heartbeat_seconds",
+        "live.keepalive_seconds",
+    ] {
+        assert!(load(key, 0).is_err(), "{key}");
+    }
+}
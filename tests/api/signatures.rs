@@ -0,0 +1,242 @@
+use time::{Duration, OffsetDateTime, format_description::well_known::Rfc3339};
+
+use crate::helpers::{ReportRequest, request_signature, run_server};
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_returns_201_for_valid_v2_signature() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // Version 2 signatures cover the raw body, so subsecond timestamps and arbitrary number
+    // formatting are accepted as sent.
+    let body = r#"{"timestamp":"2023-01-01T00:00:00.123+00:00","latitude":1.5,"longitude":-2,"altitude":3,"speed":4,"bearing":5,"accuracy":6}"#;
+    let path = format!("/api/v1/devices/{api_key}/reports");
+
+    let response = server.post_signed(&path, &api_secret, body).await;
+
+    assert_eq!(
+        201,
+        response.status().as_u16(),
+        "The API did not return a 201 Created for a valid version 2 signature",
+    );
+
+    let timestamp = sqlx::query_scalar!("SELECT timestamp FROM reports")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to fetch submitted report");
+
+    assert_eq!(
+        OffsetDateTime::parse("2023-01-01T00:00:00.123+00:00", &Rfc3339).unwrap(),
+        timestamp
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_returns_401_for_invalid_v2_signature() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let request = ReportRequest::new("2023-01-01T00:00:00+00:00", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let body = serde_json::to_string(&request).unwrap();
+    let path = format!("/api/v1/devices/{api_key}/reports");
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let skewed = (OffsetDateTime::now_utc() - Duration::hours(1)).unix_timestamp();
+
+    let test_cases = vec![
+        (
+            request_signature(&api_secret, "POST", &path, &now.to_string(), "{}"),
+            now,
+            "signature over a different body",
+        ),
+        (
+            request_signature(&api_secret, "POST", "/other", &now.to_string(), &body),
+            now,
+            "signature over a different path",
+        ),
+        (
+            request_signature(&api_secret, "PUT", &path, &now.to_string(), &body),
+            now,
+            "signature over a different method",
+        ),
+        (
+            request_signature(&api_secret, "POST", &path, &(now - 1).to_string(), &body),
+            now,
+            "signature over a different timestamp",
+        ),
+        (
+            request_signature(&api_secret, "POST", &path, &skewed.to_string(), &body),
+            skewed,
+            "timestamp outside the allowed clock skew",
+        ),
+    ];
+
+    for (signature, timestamp, description) in test_cases {
+        let response = reqwest::Client::new()
+            .post(format!("{}{path}", server.base_url))
+            .header("Content-Type", "application/json")
+            .header("X-Signature", signature)
+            .header("X-Signature-Timestamp", timestamp.to_string())
+            .header("X-Signature-Version", "2")
+            .body(body.clone())
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(
+            401,
+            response.status().as_u16(),
+            "The API did not return a 401 Unauthorized for an invalid version 2 signature: {description}",
+        );
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_report_returns_409_for_replayed_v2_signature() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let body = r#"{"timestamp":"2023-01-01T00:00:00+00:00","latitude":0,"longitude":0,"altitude":0,"speed":0,"bearing":0,"accuracy":0}"#;
+    let path = format!("/api/v1/devices/{api_key}/reports");
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp().to_string();
+    let signature = request_signature(&api_secret, "POST", &path, &timestamp, body);
+
+    let mut statuses = Vec::new();
+
+    for _ in 0..2 {
+        let response = reqwest::Client::new()
+            .post(format!("{}{path}", server.base_url))
+            .header("Content-Type", "application/json")
+            .header("X-Signature", signature.clone())
+            .header("X-Signature-Timestamp", timestamp.clone())
+            .header("X-Signature-Version", "2")
+            .body(body)
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        statuses.push(response.status().as_u16());
+    }
+
+    assert_eq!(
+        vec![201, 409],
+        statuses,
+        "The API did not reject a replayed version 2 signature",
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_report_returns_409_for_replayed_v2_signature_in_other_case() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let body = r#"{"timestamp":"2023-01-01T00:00:00+00:00","latitude":0,"longitude":0,"altitude":0,"speed":0,"bearing":0,"accuracy":0}"#;
+    let path = format!("/api/v1/devices/{api_key}/reports");
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp().to_string();
+    let signature = request_signature(&api_secret, "POST", &path, &timestamp, body);
+
+    let mut statuses = Vec::new();
+
+    for signature in [signature.clone(), signature.to_ascii_uppercase()] {
+        let response = reqwest::Client::new()
+            .post(format!("{}{path}", server.base_url))
+            .header("Content-Type", "application/json")
+            .header("X-Signature", signature)
+            .header("X-Signature-Timestamp", timestamp.clone())
+            .header("X-Signature-Version", "2")
+            .body(body)
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        statuses.push(response.status().as_u16());
+    }
+
+    assert_eq!(
+        vec![201, 409],
+        statuses,
+        "The API did not reject a version 2 signature replayed in upper case",
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_report_returns_400_for_invalid_v2_signed_report() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let path = format!("/api/v1/devices/{api_key}/reports");
+
+    let response = server
+        .post_signed(
+            &path,
+            &api_secret,
+            r#"{"timestamp":"2023-01-01T00:00:00+00:00","latitude":91,"longitude":0,"altitude":0,"speed":0,"bearing":0,"accuracy":0}"#,
+        )
+        .await;
+
+    assert_eq!(400, response.status().as_u16());
+
+    let response = reqwest::Client::new()
+        .post(format!("{}{path}", server.base_url))
+        .header("Content-Type", "text/plain")
+        .body(r#"{"timestamp":"2023-01-01T00:00:00+00:00","latitude":0,"longitude":0,"altitude":0,"speed":0,"bearing":0,"accuracy":0}"#)
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+#[expect(clippy::unwrap_used)]
+async fn post_report_batch_accepts_v2_signature_over_whole_batch() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let body = serde_json::to_string(&vec![
+        ReportRequest::new("2023-01-01T00:00:00+00:00", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+        ReportRequest::new("2023-01-01T00:00:01+00:00", 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+    ])
+    .unwrap();
+
+    let response = server
+        .post_signed(
+            &format!("/api/v1/devices/{api_key}/reports/batch"),
+            &api_secret,
+            &body,
+        )
+        .await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body: serde_json::Value = response.json().await.unwrap();
+
+    assert_eq!(2, body["accepted"]);
+    assert_eq!(0, body["rejected"]);
+}
@@ -0,0 +1,318 @@
+use serde_json::json;
+
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+/// Reports in Utah, in a smaller area within Utah, in London and near the antimeridian.
+fn report_requests() -> Vec<ReportRequest> {
+    vec![
+        ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -111.5),
+        ReportRequest::at("2023-01-01T00:01:00+00:00", 40.5, -111.9),
+        ReportRequest::at("2023-01-01T00:02:00+00:00", 51.5, -0.1),
+        ReportRequest::at("2023-01-01T00:03:00+00:00", 10.0, 179.5),
+    ]
+}
+
+#[expect(clippy::expect_used)]
+async fn get(server: &TestApplication, path: &str) -> reqwest::Response {
+    reqwest::get(format!("{}{path}", server.base_url))
+        .await
+        .expect("Failed to execute request")
+}
+
+#[expect(clippy::expect_used)]
+async fn search(server: &TestApplication, path: &str, body: String) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{}{path}", server.base_url))
+        .header("Content-Type", "application/geo+json")
+        .body(body)
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+fn timestamps(reports: &[serde_json::Value]) -> Vec<&str> {
+    reports
+        .iter()
+        .map(|report| report["timestamp"].as_str().unwrap_or_default())
+        .collect()
+}
+
+/// A square around the Utah reports with a hole around the second of them.
+fn polygon_with_hole() -> String {
+    json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": {
+            "type": "Polygon",
+            "coordinates": [
+                [[-112.0, 39.0], [-111.0, 39.0], [-111.0, 41.0], [-112.0, 41.0], [-112.0, 39.0]],
+                [[-112.0, 40.25], [-111.75, 40.25], [-111.75, 40.75], [-112.0, 40.75], [-112.0, 40.25]],
+            ],
+        },
+    })
+    .to_string()
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_reports_filters_by_bbox() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let response = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports?order=asc&bbox=-112,39,-111,41"),
+    )
+    .await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let reports: Vec<serde_json::Value> = response.json().await.expect("Failed to parse reports");
+
+    assert_eq!(
+        vec![
+            "+002023-01-01T00:00:00.000000000Z",
+            "+002023-01-01T00:01:00.000000000Z"
+        ],
+        timestamps(&reports)
+    );
+
+    let count: serde_json::Value = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/count?bbox=-112,39,-111,41"),
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse count");
+
+    assert_eq!(json!({ "count": 2 }), count);
+
+    // A bounding box crossing the antimeridian.
+    let reports: Vec<serde_json::Value> = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports?bbox=179,0,-179,20"),
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse reports");
+
+    assert_eq!(
+        vec!["+002023-01-01T00:03:00.000000000Z"],
+        timestamps(&reports)
+    );
+
+    let gpx = get(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports.gpx?bbox=-1,51,1,52"),
+    )
+    .await
+    .text()
+    .await
+    .expect("Failed to read GPX");
+
+    assert_eq!(1, gpx.matches("<trkpt ").count());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_report_search_filters_by_polygon() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let response = search(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/search"),
+        polygon_with_hole(),
+    )
+    .await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let reports: Vec<serde_json::Value> = response.json().await.expect("Failed to parse reports");
+
+    assert_eq!(
+        vec!["+002023-01-01T00:00:00.000000000Z"],
+        timestamps(&reports)
+    );
+
+    let count: serde_json::Value = search(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/search/count"),
+        polygon_with_hole(),
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse count");
+
+    assert_eq!(json!({ "count": 1 }), count);
+
+    let gpx = search(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/search.gpx"),
+        polygon_with_hole(),
+    )
+    .await
+    .text()
+    .await
+    .expect("Failed to read GPX");
+
+    assert_eq!(1, gpx.matches("<trkpt ").count());
+
+    // The time range is applied in addition to the polygon.
+    let reports: Vec<serde_json::Value> = search(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/search?since=2023-01-01T00:00:30Z"),
+        polygon_with_hole(),
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse reports");
+
+    assert!(reports.is_empty());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_report_search_supports_polygons_crossing_the_antimeridian() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let polygon = json!({
+        "type": "Polygon",
+        "coordinates": [[[179.0, 0.0], [-179.0, 0.0], [-179.0, 20.0], [179.0, 20.0], [179.0, 0.0]]],
+    })
+    .to_string();
+
+    let reports: Vec<serde_json::Value> = search(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/search"),
+        polygon,
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse reports");
+
+    assert_eq!(
+        vec!["+002023-01-01T00:03:00.000000000Z"],
+        timestamps(&reports)
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn post_report_search_applies_holes_to_their_own_polygon() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    // The second polygon is an island within the hole of the first, around the second report.
+    let multipolygon = json!({
+        "type": "MultiPolygon",
+        "coordinates": [
+            [
+                [[-112.0, 39.0], [-111.0, 39.0], [-111.0, 41.0], [-112.0, 41.0], [-112.0, 39.0]],
+                [[-112.0, 40.25], [-111.75, 40.25], [-111.75, 40.75], [-112.0, 40.75], [-112.0, 40.25]],
+            ],
+            [
+                [[-111.95, 40.45], [-111.85, 40.45], [-111.85, 40.55], [-111.95, 40.55], [-111.95, 40.45]],
+            ],
+        ],
+    })
+    .to_string();
+
+    let reports: Vec<serde_json::Value> = search(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/search?order=asc"),
+        multipolygon,
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse reports");
+
+    assert_eq!(
+        vec![
+            "+002023-01-01T00:00:00.000000000Z",
+            "+002023-01-01T00:01:00.000000000Z"
+        ],
+        timestamps(&reports)
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn spatial_filters_return_400_for_invalid_areas() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    for bbox in ["1,2,3", "a,b,c,d", "-112,41,-111,39", "-112,39,-111,91"] {
+        let response = get(
+            &server,
+            &format!("/api/v1/devices/{api_key}/reports?bbox={bbox}"),
+        )
+        .await;
+
+        assert_eq!(400, response.status().as_u16(), "bbox={bbox}");
+    }
+
+    let unclosed = json!({
+        "type": "Polygon",
+        "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]],
+    });
+    let point = json!({ "type": "Point", "coordinates": [0.0, 0.0] });
+
+    for body in [unclosed.to_string(), point.to_string(), "{".to_string()] {
+        let response = search(
+            &server,
+            &format!("/api/v1/devices/{api_key}/reports/search"),
+            body,
+        )
+        .await;
+
+        assert_eq!(400, response.status().as_u16());
+    }
+
+    let response = search(
+        &server,
+        &format!("/api/v1/devices/{api_key}/reports/search?bbox=-112,39,-111,41"),
+        polygon_with_hole(),
+    )
+    .await;
+
+    assert_eq!(400, response.status().as_u16());
+}
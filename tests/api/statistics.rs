@@ -0,0 +1,106 @@
+use serde_json::json;
+
+use crate::helpers::{ReportRequest, run_server};
+
+/// Two kilometers northward climbing 10 m with some noise, then a stop while descending 7 m.
+fn report_requests() -> Vec<ReportRequest> {
+    vec![
+        ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -111.5)
+            .with_altitude(1000.0)
+            .with_speed(10.0),
+        ReportRequest::at("2023-01-01T00:01:00+00:00", 40.01, -111.5)
+            .with_altitude(1002.0)
+            .with_speed(18.5),
+        ReportRequest::at("2023-01-01T00:02:00+00:00", 40.02, -111.5)
+            .with_altitude(1010.0)
+            .with_speed(15.0),
+        ReportRequest::at("2023-01-01T00:03:00+00:00", 40.02, -111.5).with_altitude(1003.0),
+    ]
+}
+
+fn assert_statistics(statistics: &serde_json::Value) {
+    assert_eq!(4, statistics["reports"]);
+    assert_eq!(json!(180.0), statistics["duration"]);
+    assert_eq!(json!(120.0), statistics["moving_time"]);
+    assert_eq!(json!(18.5), statistics["max_speed"]);
+    assert_eq!(json!(10.0), statistics["ascent"]);
+    assert_eq!(json!(7.0), statistics["descent"]);
+
+    assert!(
+        statistics["distance"]
+            .as_f64()
+            .is_some_and(|distance| (2220.0..2230.0).contains(&distance))
+    );
+    assert!(
+        statistics["average_speed"]
+            .as_f64()
+            .is_some_and(|speed| (18.5..18.6).contains(&speed))
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_statistics_summarizes_range() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let response = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/statistics",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let statistics: serde_json::Value = response.json().await.expect("Failed to parse statistics");
+
+    assert_statistics(&statistics);
+
+    let statistics: serde_json::Value = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/statistics?since=2023-01-01T00:05:00Z",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+    .json()
+    .await
+    .expect("Failed to parse statistics");
+
+    assert_eq!(0, statistics["reports"]);
+    assert_eq!(json!(0.0), statistics["distance"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_trips_includes_statistics() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let trips: Vec<serde_json::Value> = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/trips",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+    .json()
+    .await
+    .expect("Failed to parse trips");
+
+    assert_eq!(1, trips.len());
+    assert_statistics(&trips[0]["statistics"]);
+}
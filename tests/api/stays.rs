@@ -0,0 +1,110 @@
+use serde_json::json;
+
+use crate::helpers::{ReportRequest, run_server};
+
+/// A ten minute stay, interrupted by a single inaccurate report, followed by a brief stop.
+fn report_requests() -> Vec<ReportRequest> {
+    vec![
+        ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -105.0).with_accuracy(5.0),
+        ReportRequest::at("2023-01-01T00:02:00+00:00", 40.0001, -105.0).with_accuracy(5.0),
+        ReportRequest::at("2023-01-01T00:04:00+00:00", 40.1, -105.0).with_accuracy(500.0),
+        ReportRequest::at("2023-01-01T00:06:00+00:00", 40.0, -105.0).with_accuracy(5.0),
+        ReportRequest::at("2023-01-01T00:08:00+00:00", 40.0001, -105.0).with_accuracy(10.0),
+        ReportRequest::at("2023-01-01T00:10:00+00:00", 40.0, -105.0).with_accuracy(5.0),
+        ReportRequest::at("2023-01-01T00:11:00+00:00", 40.01, -105.0).with_accuracy(5.0),
+        ReportRequest::at("2023-01-01T00:12:00+00:00", 40.02, -105.0).with_accuracy(5.0),
+        ReportRequest::at("2023-01-01T00:13:00+00:00", 40.0201, -105.0).with_accuracy(5.0),
+    ]
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_stays_detects_stays() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let stays: Vec<serde_json::Value> = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/stays",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+    .json()
+    .await
+    .expect("Failed to parse stays");
+
+    assert_eq!(1, stays.len());
+    assert_eq!(5, stays[0]["reports"]);
+    assert_eq!(json!(600.0), stays[0]["duration"]);
+    assert_eq!(
+        "00:00",
+        &stays[0]["arrival"].as_str().unwrap_or_default()[14..19]
+    );
+    assert_eq!(
+        "00:10",
+        &stays[0]["departure"].as_str().unwrap_or_default()[14..19]
+    );
+    assert!(
+        stays[0]["latitude"]
+            .as_f64()
+            .is_some_and(|latitude| (40.0..40.0001).contains(&latitude))
+    );
+    assert!(
+        stays[0]["longitude"]
+            .as_f64()
+            .is_some_and(|longitude| (longitude + 105.0).abs() < 1e-9)
+    );
+
+    let stays: Vec<serde_json::Value> = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/stays?duration=60",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+    .json()
+    .await
+    .expect("Failed to parse stays");
+
+    assert_eq!(2, stays.len());
+    assert_eq!(2, stays[1]["reports"]);
+
+    let summaries: Vec<serde_json::Value> = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/summaries?from=2023-01-01&to=2023-01-01",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+    .json()
+    .await
+    .expect("Failed to parse summaries");
+
+    assert_eq!(1, summaries[0]["stays"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_stays_rejects_invalid_thresholds() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    for query in ["radius=0", "duration=0", "radius=-5"] {
+        let response = reqwest::get(format!(
+            "{}/api/v1/devices/{api_key}/stays?{query}",
+            server.base_url
+        ))
+        .await
+        .expect("Failed to execute request");
+
+        assert_eq!(400, response.status().as_u16(), "{query}");
+    }
+}
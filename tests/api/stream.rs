@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use sqlx::types::BigDecimal;
+use time::OffsetDateTime;
+
+use com_calindora_follow::live;
+use com_calindora_follow::models::{CreateReportRequest, Report};
+
+use crate::helpers::{TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn open_stream(
+    server: &TestApplication,
+    api_key: &str,
+    last_event_id: Option<&str>,
+) -> reqwest::Response {
+    let mut request = reqwest::Client::new().get(format!(
+        "{}/api/v1/devices/{api_key}/reports/stream",
+        server.base_url
+    ));
+
+    if let Some(last_event_id) = last_event_id {
+        request = request.header("Last-Event-ID", last_event_id);
+    }
+
+    request.send().await.expect("Failed to execute request")
+}
+
+/// Reads from an event stream until the given number of report events have been received.
+#[expect(clippy::expect_used)]
+async fn read_events(response: &mut reqwest::Response, count: usize) -> String {
+    let mut body = String::new();
+
+    while body.matches("event: report\n").count() < count {
+        let chunk = actix_web::rt::time::timeout(Duration::from_secs(5), response.chunk())
+            .await
+            .expect("Timed out waiting for an event")
+            .expect("Failed to read event stream")
+            .expect("The event stream ended unexpectedly");
+
+        body.push_str(std::str::from_utf8(&chunk).expect("The event stream was not UTF-8"));
+    }
+
+    body
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn report_stream_sends_new_reports() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+    let (other_api_key, other_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let mut response = open_stream(&server, &api_key, None).await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        Some("text/event-stream"),
+        response
+            .headers()
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+    );
+
+    // Reports of other devices are not sent.
+    server
+        .submit_report(
+            &other_api_key,
+            &other_api_secret,
+            "2023-01-01T00:00:00+00:00",
+        )
+        .await;
+
+    let id = server
+        .submit_report(&api_key, &api_secret, "2023-01-01T00:01:00+00:00")
+        .await;
+
+    let body = read_events(&mut response, 1).await;
+
+    assert!(body.starts_with(": connected\n\n"));
+    assert!(body.contains(&format!("id: {id}\nevent: report\ndata: {{")));
+    assert!(body.contains(r#""latitude":"40""#));
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn report_stream_sends_reports_published_by_other_instances() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let device_id = sqlx::query_scalar!("SELECT id FROM devices WHERE api_key = $1", api_key)
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to fetch device");
+
+    let mut response = open_stream(&server, &api_key, None).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    // Simulate another instance storing and publishing a report using the shared database.
+    let request = CreateReportRequest {
+        id: None,
+        timestamp: OffsetDateTime::now_utc(),
+        latitude: BigDecimal::from(40),
+        longitude: BigDecimal::from(-111),
+        altitude: BigDecimal::from(0),
+        speed: BigDecimal::from(0),
+        bearing: BigDecimal::from(0),
+        accuracy: BigDecimal::from(0),
+        nonce: None,
+    };
+
+    let report = Report::create(&server.db, device_id, &request, None)
+        .await
+        .expect("Failed to insert report")
+        .expect("The report was not inserted");
+
+    live::notify(&server.db, &report)
+        .await
+        .expect("Failed to publish report");
+
+    let body = read_events(&mut response, 1).await;
+
+    assert!(body.contains(&format!("id: {}\n", report.id)));
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn report_stream_resumes_after_last_event_id() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let first = server
+        .submit_report(&api_key, &api_secret, "2023-01-01T00:00:00+00:00")
+        .await;
+    let second = server
+        .submit_report(&api_key, &api_secret, "2023-01-01T00:01:00+00:00")
+        .await;
+    let third = server
+        .submit_report(&api_key, &api_secret, "2023-01-01T00:02:00+00:00")
+        .await;
+
+    let mut response = open_stream(&server, &api_key, Some(&first)).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let body = read_events(&mut response, 2).await;
+
+    assert!(!body.contains(&format!("id: {first}\n")));
+
+    let second_position = body
+        .find(&format!("id: {second}\n"))
+        .expect("The second report was not replayed");
+    let third_position = body
+        .find(&format!("id: {third}\n"))
+        .expect("The third report was not replayed");
+
+    assert!(second_position < third_position);
+
+    // New reports continue to be sent after the replay.
+    let fourth = server
+        .submit_report(&api_key, &api_secret, "2023-01-01T00:03:00+00:00")
+        .await;
+    let body = read_events(&mut response, 1).await;
+
+    assert!(body.contains(&format!("id: {fourth}\n")));
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn report_stream_rejects_invalid_last_event_id() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = open_stream(&server, &api_key, Some("not-a-uuid")).await;
+
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[actix_web::test]
+async fn report_stream_returns_404_for_unknown_device() {
+    let server = run_server().await;
+
+    let response = open_stream(&server, "unknown", None).await;
+
+    assert_eq!(404, response.status().as_u16());
+}
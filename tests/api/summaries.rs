@@ -0,0 +1,285 @@
+use serde_json::json;
+
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn get_summaries(server: &TestApplication, api_key: &str, query: &str) -> reqwest::Response {
+    reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/summaries?{query}",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_summaries_uses_local_days() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    // Every report is on January 2nd in UTC, but the first two are on January 1st in Denver.
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-02T05:00:00+00:00", 40.0, -105.0),
+                ReportRequest::at("2023-01-02T05:01:00+00:00", 40.01, -105.0),
+                ReportRequest::at("2023-01-02T08:00:00+00:00", 40.01, -105.0),
+            ],
+        )
+        .await;
+
+    let summaries: Vec<serde_json::Value> = get_summaries(
+        &server,
+        &api_key,
+        "from=2022-12-31&to=2023-01-02&tz=America/Denver",
+    )
+    .await
+    .json()
+    .await
+    .expect("Failed to parse summaries");
+
+    assert_eq!(3, summaries.len());
+
+    assert_eq!("2022-12-31", summaries[0]["date"]);
+    assert_eq!(0, summaries[0]["reports"]);
+    assert_eq!(json!(0.0), summaries[0]["distance"]);
+    assert!(summaries[0]["first_report"].is_null());
+    assert!(summaries[0]["bbox"].is_null());
+
+    assert_eq!("2023-01-01", summaries[1]["date"]);
+    assert_eq!(2, summaries[1]["reports"]);
+    assert_eq!(1, summaries[1]["trips"]);
+    assert_eq!(json!(60.0), summaries[1]["moving_time"]);
+    assert!(
+        summaries[1]["distance"]
+            .as_f64()
+            .is_some_and(|distance| (1100.0..1120.0).contains(&distance))
+    );
+    assert_eq!(json!([-105.0, 40.0, -105.0, 40.01]), summaries[1]["bbox"]);
+    assert!(
+        summaries[1]["first_report"]
+            .as_str()
+            .is_some_and(|timestamp| timestamp.starts_with("+002023-01-02T05:00:00"))
+    );
+    assert!(
+        summaries[1]["last_report"]
+            .as_str()
+            .is_some_and(|timestamp| timestamp.starts_with("+002023-01-02T05:01:00"))
+    );
+
+    assert_eq!("2023-01-02", summaries[2]["date"]);
+    assert_eq!(1, summaries[2]["reports"]);
+    assert_eq!(0, summaries[2]["trips"]);
+
+    let summaries: Vec<serde_json::Value> =
+        get_summaries(&server, &api_key, "from=2023-01-02&to=2023-01-02")
+            .await
+            .json()
+            .await
+            .expect("Failed to parse summaries");
+
+    assert_eq!(1, summaries.len());
+    assert_eq!(3, summaries[0]["reports"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_summaries_includes_late_reports() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[ReportRequest::at("2023-01-01T12:00:00+00:00", 40.0, -105.0)],
+        )
+        .await;
+
+    let query = "from=2023-01-01&to=2023-01-01";
+
+    let summaries: Vec<serde_json::Value> = get_summaries(&server, &api_key, query)
+        .await
+        .json()
+        .await
+        .expect("Failed to parse summaries");
+
+    assert_eq!(1, summaries[0]["reports"]);
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[ReportRequest::at(
+                "2023-01-01T11:00:00+00:00",
+                40.01,
+                -105.0,
+            )],
+        )
+        .await;
+
+    let summaries: Vec<serde_json::Value> = get_summaries(&server, &api_key, query)
+        .await
+        .json()
+        .await
+        .expect("Failed to parse summaries");
+
+    assert_eq!(2, summaries[0]["reports"]);
+    assert!(
+        summaries[0]["first_report"]
+            .as_str()
+            .is_some_and(|timestamp| timestamp.starts_with("+002023-01-01T11:00:00"))
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_summaries_recomputes_summaries_cached_with_other_thresholds() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[ReportRequest::at("2023-01-01T12:00:00+00:00", 40.0, -105.0)],
+        )
+        .await;
+
+    let query = "from=2023-01-01&to=2023-01-01";
+    let response = get_summaries(&server, &api_key, query).await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Simulate a summary cached before the thresholds were changed.
+    sqlx::query!("UPDATE daily_summaries SET reports = 42, thresholds = 'previous'")
+        .execute(&server.db)
+        .await
+        .expect("Failed to update daily summaries");
+
+    let summaries: Vec<serde_json::Value> = get_summaries(&server, &api_key, query)
+        .await
+        .json()
+        .await
+        .expect("Failed to parse summaries");
+
+    assert_eq!(1, summaries[0]["reports"]);
+
+    let thresholds = sqlx::query_scalar!("SELECT thresholds FROM daily_summaries")
+        .fetch_all(&server.db)
+        .await
+        .expect("Failed to fetch daily summaries");
+
+    assert_eq!(1, thresholds.len());
+    assert_ne!("previous", thresholds[0]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_summaries_caches_days_without_reports_submitted_during_the_request() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(
+            &api_key,
+            &api_secret,
+            &[
+                ReportRequest::at("2023-01-01T12:00:00+00:00", 40.0, -105.0),
+                ReportRequest::at("2023-01-03T12:00:00+00:00", 40.0, -105.0),
+            ],
+        )
+        .await;
+
+    // Submit a report for a later day while holding the device, as a concurrent report would, so
+    // that the request must wait for it before caching any summary.
+    let mut transaction = server
+        .db
+        .begin()
+        .await
+        .expect("Failed to begin a transaction");
+
+    sqlx::query!(
+        r#"WITH sequence AS (
+            UPDATE devices SET last_report_sequence = last_report_sequence + 1
+            WHERE api_key = $1
+            RETURNING id, last_report_sequence
+        )
+        INSERT INTO reports (id, device_id, timestamp, latitude, longitude, altitude, speed,
+            bearing, accuracy, sequence)
+        SELECT gen_random_uuid(), id, '2023-01-05T12:00:00Z', 40, -105, 1600, 0, 0, 5,
+            last_report_sequence
+        FROM sequence"#,
+        api_key
+    )
+    .execute(&mut *transaction)
+    .await
+    .expect("Failed to insert a report");
+
+    let request = get_summaries(&server, &api_key, "from=2023-01-01&to=2023-01-03");
+
+    let submit = async {
+        while sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM pg_stat_activity
+            WHERE datname = current_database() AND wait_event_type = 'Lock'"#
+        )
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to query locks")
+            == 0
+        {
+            actix_web::rt::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        transaction
+            .commit()
+            .await
+            .expect("Failed to commit the report");
+    };
+
+    let (response, ()) = futures::join!(request, submit);
+
+    assert_eq!(200, response.status().as_u16());
+
+    let days = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM daily_summaries"#)
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to count daily summaries");
+
+    assert_eq!(3, days);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_summaries_rejects_invalid_parameters() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    for query in [
+        "from=2023-01-01&to=2023-01-01&tz=Mars/Olympus_Mons",
+        "from=2023-01-02&to=2023-01-01",
+        "from=2020-01-01&to=2023-01-01",
+        "from=2023-01-01",
+    ] {
+        let response = get_summaries(&server, &api_key, query).await;
+
+        assert_eq!(400, response.status().as_u16(), "{query}");
+    }
+}
@@ -0,0 +1,298 @@
+use std::str::FromStr;
+
+use bigdecimal::ToPrimitive;
+use sqlx::types::BigDecimal;
+use time::Duration;
+use uuid::Uuid;
+
+use com_calindora_follow::import::run_import_job;
+use com_calindora_follow::models::{ImportJob, ImportJobStatus};
+
+use crate::helpers::{TestApplication, run_server};
+
+const RECORDS: &str = r#"{
+  "locations": [{
+    "latitudeE7": 407127281,
+    "longitudeE7": -740059731,
+    "accuracy": 14,
+    "altitude": 12,
+    "velocity": 3,
+    "heading": 180,
+    "source": "WIFI",
+    "timestamp": "2016-03-01T10:00:00.000Z"
+  }, {
+    "latitudeE7": 4294967200,
+    "longitudeE7": -740059731,
+    "accuracy": 20,
+    "timestampMs": "1456826460000"
+  }, {
+    "latitudeE7": 407127281,
+    "timestamp": "2016-03-01T10:02:00Z"
+  }, {
+    "latitudeE7": 407137281,
+    "longitudeE7": -740059731,
+    "accuracy": 10,
+    "timestamp": "2016-03-01T10:03:00Z",
+    "activity": [{ "activity": [{ "type": "STILL", "confidence": 100 }] }]
+  }]
+}"#;
+
+const TIMELINE: &str = r#"{
+  "semanticSegments": [{
+    "startTime": "2024-05-01T08:00:00.000-07:00",
+    "endTime": "2024-05-01T09:00:00.000-07:00",
+    "timelinePath": [
+      { "point": "37.7749000°, -122.4194000°", "time": "2024-05-01T08:00:00.000-07:00" },
+      { "point": "37.7759000°, -122.4194000°", "time": "2024-05-01T08:02:00.000-07:00" }
+    ]
+  }, {
+    "startTime": "2024-05-01T09:00:00.000-07:00",
+    "endTime": "2024-05-01T10:00:00.000-07:00",
+    "visit": { "topCandidate": { "placeId": "abc", "placeLocation": { "latLng": "37.7800000°, -122.4100000°" } } }
+  }],
+  "rawSignals": [{
+    "position": {
+      "LatLng": "37.7700000°, -122.4200000°",
+      "accuracyMeters": 8,
+      "altitudeMeters": 20.5,
+      "speedMetersPerSecond": 1.25,
+      "timestamp": "2024-05-01T07:59:00.000-07:00"
+    }
+  }, {
+    "wifiScan": { "deliveryTime": "2024-05-01T07:59:00.000-07:00" }
+  }],
+  "userLocationProfile": { "frequentPlaces": [] }
+}"#;
+
+/// Waits for an import job to finish, returning its final state.
+#[expect(clippy::expect_used)]
+async fn wait_for_job(server: &TestApplication, location: &str) -> serde_json::Value {
+    for _ in 0..100 {
+        let job: serde_json::Value = reqwest::get(format!("{}{location}", server.base_url))
+            .await
+            .expect("Failed to execute request")
+            .json()
+            .await
+            .expect("Failed to parse import job");
+
+        if job["status"] != "pending" && job["status"] != "running" {
+            return job;
+        }
+
+        actix_web::rt::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    panic!("The import job did not finish");
+}
+
+#[expect(clippy::expect_used)]
+async fn upload(server: &TestApplication, api_key: &str, api_secret: &str, body: &str) -> String {
+    let response = server
+        .post_signed(
+            &format!("/api/v1/devices/{api_key}/imports/takeout"),
+            api_secret,
+            body,
+        )
+        .await;
+
+    assert_eq!(202, response.status().as_u16());
+
+    response
+        .headers()
+        .get("Location")
+        .and_then(|value| value.to_str().ok())
+        .expect("The API did not return a Location header")
+        .to_string()
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn takeout_records_are_imported_in_background() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let location = upload(&server, &api_key, &api_secret, RECORDS).await;
+    let job = wait_for_job(&server, &location).await;
+
+    assert_eq!("completed", job["status"]);
+    assert_eq!(4, job["processed_records"]);
+    assert_eq!(3, job["imported"]);
+    assert_eq!(0, job["duplicates"]);
+    assert_eq!(1, job["rejected"]);
+    assert_eq!(RECORDS.len(), job["total_bytes"]);
+
+    let reports = sqlx::query!("SELECT * FROM reports ORDER BY timestamp")
+        .fetch_all(&server.db)
+        .await
+        .expect("Failed to fetch imported reports");
+
+    assert_eq!(3, reports.len());
+    assert_eq!(
+        BigDecimal::from_str("40.7127281").unwrap_or_default(),
+        reports[0].latitude
+    );
+    assert_eq!(
+        BigDecimal::from_str("-74.0059731").unwrap_or_default(),
+        reports[0].longitude
+    );
+    assert_eq!(BigDecimal::from(14), reports[0].accuracy);
+    assert_eq!(BigDecimal::from(3), reports[0].speed);
+    assert_eq!(BigDecimal::from(180), reports[0].bearing);
+
+    // The overflowed latitude is corrected.
+    assert_eq!(
+        BigDecimal::from_str("-0.0000096").unwrap_or_default(),
+        reports[1].latitude
+    );
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn takeout_timeline_is_imported_in_background() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let location = upload(&server, &api_key, &api_secret, TIMELINE).await;
+    let job = wait_for_job(&server, &location).await;
+
+    assert_eq!("completed", job["status"]);
+    assert_eq!(4, job["imported"]);
+    assert_eq!(0, job["rejected"]);
+
+    let report = sqlx::query!("SELECT * FROM reports ORDER BY timestamp LIMIT 1")
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to fetch imported reports");
+
+    assert_eq!(
+        BigDecimal::from_str("1.25").unwrap_or_default(),
+        report.speed
+    );
+    assert_eq!(
+        BigDecimal::from_str("20.5").unwrap_or_default(),
+        report.altitude
+    );
+    assert_eq!(BigDecimal::from(8), report.accuracy);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn takeout_import_resumes_from_recorded_progress() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let device_id = sqlx::query_scalar!("SELECT id FROM devices WHERE api_key = $1", api_key)
+        .fetch_one(&server.db)
+        .await
+        .expect("Failed to fetch device");
+
+    let path = std::env::temp_dir().join(format!("{}.json", Uuid::new_v4()));
+    std::fs::write(&path, RECORDS).expect("Failed to write import file");
+
+    let job = ImportJob::create(
+        &server.db,
+        Uuid::new_v4(),
+        device_id,
+        &path.to_string_lossy(),
+        false,
+        0,
+    )
+    .await
+    .expect("Failed to create import job");
+
+    // Simulate a job that was interrupted after processing the first two records.
+    sqlx::query!(
+        r#"UPDATE import_jobs
+        SET status = 'running', processed_records = 2, imported = 1, rejected = 1,
+            previous_timestamp = '2016-03-01T10:01:00Z', previous_latitude = -0.0000096,
+            previous_longitude = -74.0059731
+        WHERE id = $1"#,
+        job.id
+    )
+    .execute(&server.db)
+    .await
+    .expect("Failed to update import job");
+
+    let job = run_import_job(&server.db, job)
+        .await
+        .expect("Failed to run import job");
+
+    assert_eq!(ImportJobStatus::Completed, job.status);
+    assert_eq!(4, job.processed_records);
+    assert_eq!(2, job.imported);
+    assert_eq!(2, job.rejected);
+
+    let reports = sqlx::query!("SELECT * FROM reports ORDER BY timestamp")
+        .fetch_all(&server.db)
+        .await
+        .expect("Failed to fetch imported reports");
+
+    assert_eq!(1, reports.len());
+    assert_eq!(
+        time::macros::datetime!(2016-03-01 10:00:00 UTC) + Duration::minutes(3),
+        reports[0].timestamp
+    );
+
+    // The speed and bearing are derived from the point recorded before the interruption.
+    let speed = reports[0].speed.to_f64().unwrap_or_default();
+    assert!((speed - 37_728.0).abs() < 10.0, "Unexpected speed {speed}");
+    assert!(reports[0].bearing.to_f64().unwrap_or(f64::MAX) < 0.01);
+
+    // Files that were not uploaded are left in place.
+    assert!(path.exists());
+    std::fs::remove_file(&path).expect("Failed to remove import file");
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn takeout_import_requires_version_2_signature() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let path = format!("/api/v1/devices/{api_key}/imports/takeout");
+
+    let response = reqwest::Client::new()
+        .post(format!("{}{path}", server.base_url))
+        .body(RECORDS)
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(400, response.status().as_u16());
+
+    let response = server.post_signed(&path, "wrong", RECORDS).await;
+
+    assert_eq!(401, response.status().as_u16());
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_import_job_returns_404_for_unknown_job() {
+    let server = run_server().await;
+    let (api_key, _) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    let response = reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/imports/{}",
+        server.base_url,
+        Uuid::new_v4()
+    ))
+    .await
+    .expect("Failed to execute request");
+
+    assert_eq!(404, response.status().as_u16());
+}
@@ -0,0 +1,126 @@
+use crate::helpers::{ReportRequest, TestApplication, run_server};
+
+#[expect(clippy::expect_used)]
+async fn get_trips(server: &TestApplication, api_key: &str, query: &str) -> reqwest::Response {
+    reqwest::get(format!(
+        "{}/api/v1/devices/{api_key}/trips?{query}",
+        server.base_url
+    ))
+    .await
+    .expect("Failed to execute request")
+}
+
+/// A trip, a stop of six minutes and another trip, followed by a lone report an hour later and a
+/// third trip an hour after that.
+fn report_requests() -> Vec<ReportRequest> {
+    vec![
+        ReportRequest::at("2023-01-01T00:00:00+00:00", 40.0, -111.5),
+        ReportRequest::at("2023-01-01T00:01:00+00:00", 40.0, -111.49),
+        ReportRequest::at("2023-01-01T00:02:00+00:00", 40.0, -111.48),
+        ReportRequest::at("2023-01-01T00:03:00+00:00", 40.0001, -111.48),
+        ReportRequest::at("2023-01-01T00:04:00+00:00", 40.0, -111.4801),
+        ReportRequest::at("2023-01-01T00:05:00+00:00", 40.0001, -111.48),
+        ReportRequest::at("2023-01-01T00:06:00+00:00", 40.0, -111.48),
+        ReportRequest::at("2023-01-01T00:07:00+00:00", 40.0001, -111.4801),
+        ReportRequest::at("2023-01-01T00:08:00+00:00", 40.0, -111.48),
+        ReportRequest::at("2023-01-01T00:09:00+00:00", 40.0, -111.47),
+        ReportRequest::at("2023-01-01T00:10:00+00:00", 40.0, -111.46),
+        ReportRequest::at("2023-01-01T01:00:00+00:00", 40.1, -111.4),
+        ReportRequest::at("2023-01-01T02:00:00+00:00", 40.2, -111.4),
+        ReportRequest::at("2023-01-01T02:01:00+00:00", 40.2, -111.39),
+        ReportRequest::at("2023-01-01T02:02:00+00:00", 40.2, -111.38),
+    ]
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_trips_splits_reports_on_gaps_and_stops() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let response = get_trips(&server, &api_key, "").await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    let trips: Vec<serde_json::Value> = response.json().await.expect("Failed to parse trips");
+
+    assert_eq!(3, trips.len());
+
+    assert_eq!("+002023-01-01T00:00:00.000000000Z", trips[0]["start"]);
+    assert_eq!("+002023-01-01T00:02:00.000000000Z", trips[0]["end"]);
+    assert_eq!(3, trips[0]["statistics"]["reports"]);
+    assert_eq!(
+        serde_json::json!([-111.5, 40.0, -111.48, 40.0]),
+        trips[0]["bbox"]
+    );
+
+    assert_eq!("+002023-01-01T00:09:00.000000000Z", trips[1]["start"]);
+    assert_eq!("+002023-01-01T00:10:00.000000000Z", trips[1]["end"]);
+    assert_eq!(2, trips[1]["statistics"]["reports"]);
+
+    assert_eq!("+002023-01-01T02:00:00.000000000Z", trips[2]["start"]);
+    assert_eq!(3, trips[2]["statistics"]["reports"]);
+
+    // The link to the reports of a trip returns exactly those reports.
+    let reports_url = trips[0]["reports_url"]
+        .as_str()
+        .expect("The trip has no link to its reports");
+
+    assert!(!reports_url.contains("limit="));
+
+    let reports: Vec<serde_json::Value> = reqwest::get(format!("{}{reports_url}", server.base_url))
+        .await
+        .expect("Failed to execute request")
+        .json()
+        .await
+        .expect("Failed to parse reports");
+
+    assert_eq!(3, reports.len());
+    assert_eq!("+002023-01-01T00:00:00.000000000Z", reports[0]["timestamp"]);
+    assert_eq!("+002023-01-01T00:02:00.000000000Z", reports[2]["timestamp"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn get_trips_accepts_thresholds() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_reports(&api_key, &api_secret, &report_requests())
+        .await;
+
+    let trips: Vec<serde_json::Value> = get_trips(&server, &api_key, "gap=7200")
+        .await
+        .json()
+        .await
+        .expect("Failed to parse trips");
+
+    assert_eq!(2, trips.len());
+    assert_eq!("+002023-01-01T00:09:00.000000000Z", trips[1]["start"]);
+    assert_eq!("+002023-01-01T02:02:00.000000000Z", trips[1]["end"]);
+
+    // With a longer stationary threshold the stop no longer ends the first trip.
+    let trips: Vec<serde_json::Value> = get_trips(&server, &api_key, "stationary=600")
+        .await
+        .json()
+        .await
+        .expect("Failed to parse trips");
+
+    assert_eq!(2, trips.len());
+    assert_eq!(11, trips[0]["statistics"]["reports"]);
+
+    let response = get_trips(&server, &api_key, "gap=0").await;
+
+    assert_eq!(400, response.status().as_u16());
+}
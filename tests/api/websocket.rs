@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async, tungstenite::Message};
+
+use crate::helpers::{TestApplication, run_server};
+
+type WebSocket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+#[expect(clippy::expect_used)]
+async fn connect(server: &TestApplication) -> WebSocket {
+    let (socket, _) = connect_async(format!("ws://127.0.0.1:{}/api/v1/live", server.port))
+        .await
+        .expect("Failed to connect to WebSocket");
+
+    socket
+}
+
+#[expect(clippy::expect_used)]
+async fn send(socket: &mut WebSocket, message: &serde_json::Value) {
+    socket
+        .send(Message::text(message.to_string()))
+        .await
+        .expect("Failed to send message");
+}
+
+/// Receives the next message of the given type, skipping any others.
+#[expect(clippy::expect_used)]
+async fn receive(socket: &mut WebSocket, message_type: &str) -> serde_json::Value {
+    loop {
+        let message = actix_web::rt::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .expect("Timed out waiting for a message")
+            .expect("The WebSocket was closed")
+            .expect("Failed to receive message");
+
+        if let Message::Text(text) = message {
+            let message: serde_json::Value =
+                serde_json::from_str(&text).expect("Failed to parse message");
+
+            if message["type"] == message_type {
+                return message;
+            }
+        }
+    }
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn websocket_follows_subscribed_devices() {
+    let server = run_server().await;
+    let (first_api_key, first_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+    let (second_api_key, second_api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_report(
+            &first_api_key,
+            &first_api_secret,
+            "2023-01-01T00:00:00+00:00",
+        )
+        .await;
+
+    let mut socket = connect(&server).await;
+
+    send(
+        &mut socket,
+        &json!({ "type": "subscribe", "api_keys": [first_api_key, second_api_key, "unknown"] }),
+    )
+    .await;
+
+    let subscribed = receive(&mut socket, "subscribed").await;
+    assert_eq!(first_api_key, subscribed["api_key"]);
+    assert_eq!(
+        "+002023-01-01T00:00:00.000000000Z",
+        subscribed["latest"]["timestamp"]
+    );
+
+    let subscribed = receive(&mut socket, "subscribed").await;
+    assert_eq!(second_api_key, subscribed["api_key"]);
+    assert!(subscribed["latest"].is_null());
+
+    let error = receive(&mut socket, "error").await;
+    assert_eq!("unknown", error["api_key"]);
+
+    server
+        .submit_report(
+            &second_api_key,
+            &second_api_secret,
+            "2023-01-01T00:01:00+00:00",
+        )
+        .await;
+
+    let report = receive(&mut socket, "report").await;
+    assert_eq!(second_api_key, report["api_key"]);
+    assert_eq!(
+        "+002023-01-01T00:01:00.000000000Z",
+        report["report"]["timestamp"]
+    );
+
+    send(
+        &mut socket,
+        &json!({ "type": "unsubscribe", "api_keys": [second_api_key] }),
+    )
+    .await;
+
+    let unsubscribed = receive(&mut socket, "unsubscribed").await;
+    assert_eq!(second_api_key, unsubscribed["api_key"]);
+
+    // Reports of unsubscribed devices are no longer sent.
+    server
+        .submit_report(
+            &second_api_key,
+            &second_api_secret,
+            "2023-01-01T00:02:00+00:00",
+        )
+        .await;
+    server
+        .submit_report(
+            &first_api_key,
+            &first_api_secret,
+            "2023-01-01T00:03:00+00:00",
+        )
+        .await;
+
+    let report = receive(&mut socket, "report").await;
+    assert_eq!(first_api_key, report["api_key"]);
+}
+
+#[actix_web::test]
+#[expect(clippy::expect_used)]
+async fn websocket_sends_heartbeat_with_staleness() {
+    let server = run_server().await;
+    let (api_key, api_secret) = server
+        .create_random_device()
+        .await
+        .expect("Failed to create a test device");
+
+    server
+        .submit_report(&api_key, &api_secret, "2023-01-01T00:00:00+00:00")
+        .await;
+
+    let mut socket = connect(&server).await;
+
+    send(
+        &mut socket,
+        &json!({ "type": "subscribe", "api_keys": [api_key] }),
+    )
+    .await;
+
+    let heartbeat = receive(&mut socket, "heartbeat").await;
+
+    assert_eq!(
+        json!([{
+            "api_key": api_key,
+            "last_report": "+002023-01-01T00:00:00.000000000Z",
+            "stale": true,
+        }]),
+        heartbeat["devices"]
+    );
+}
+
+#[actix_web::test]
+async fn websocket_rejects_invalid_messages() {
+    let server = run_server().await;
+    let mut socket = connect(&server).await;
+
+    send(&mut socket, &json!({ "type": "follow" })).await;
+
+    let error = receive(&mut socket, "error").await;
+
+    assert!(error["api_key"].is_null());
+    assert!(
+        error["reason"]
+            .as_str()
+            .is_some_and(|reason| reason.starts_with("Failed to parse message"))
+    );
+}